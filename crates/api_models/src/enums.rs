@@ -47,6 +47,7 @@ pub enum PayoutConnectors {
     Adyen,
     Adyenplatform,
     Cybersource,
+    Dlocal,
     Ebanx,
     Payone,
     Paypal,
@@ -61,6 +62,7 @@ impl From<PayoutConnectors> for RoutableConnectors {
             PayoutConnectors::Adyen => Self::Adyen,
             PayoutConnectors::Adyenplatform => Self::Adyenplatform,
             PayoutConnectors::Cybersource => Self::Cybersource,
+            PayoutConnectors::Dlocal => Self::Dlocal,
             PayoutConnectors::Ebanx => Self::Ebanx,
             PayoutConnectors::Payone => Self::Payone,
             PayoutConnectors::Paypal => Self::Paypal,
@@ -77,6 +79,7 @@ impl From<PayoutConnectors> for Connector {
             PayoutConnectors::Adyen => Self::Adyen,
             PayoutConnectors::Adyenplatform => Self::Adyenplatform,
             PayoutConnectors::Cybersource => Self::Cybersource,
+            PayoutConnectors::Dlocal => Self::Dlocal,
             PayoutConnectors::Ebanx => Self::Ebanx,
             PayoutConnectors::Payone => Self::Payone,
             PayoutConnectors::Paypal => Self::Paypal,
@@ -94,6 +97,7 @@ impl TryFrom<Connector> for PayoutConnectors {
             Connector::Adyen => Ok(Self::Adyen),
             Connector::Adyenplatform => Ok(Self::Adyenplatform),
             Connector::Cybersource => Ok(Self::Cybersource),
+            Connector::Dlocal => Ok(Self::Dlocal),
             Connector::Ebanx => Ok(Self::Ebanx),
             Connector::Payone => Ok(Self::Payone),
             Connector::Paypal => Ok(Self::Paypal),
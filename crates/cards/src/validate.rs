@@ -2,7 +2,7 @@ use std::{collections::HashMap, fmt, ops::Deref, str::FromStr};
 
 use common_utils::errors::ValidationError;
 use error_stack::report;
-use masking::{PeekInterface, Strategy, StrongSecret, WithType};
+use masking::{PeekInterface, Strategy, StrongSecret, WithLastFour, WithType};
 use once_cell::sync::Lazy;
 use regex::Regex;
 #[cfg(not(target_arch = "wasm32"))]
@@ -24,6 +24,10 @@ pub struct CardNumberValidationErr(&'static str);
 #[derive(Clone, Debug, Default, Eq, PartialEq, Serialize)]
 pub struct CardNumber(StrongSecret<String, CardNumberStrategy>);
 
+/// Card number which only shows its last four digits when logged
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize)]
+pub struct LastFourCardNumber(StrongSecret<String, WithLastFour>);
+
 //Network Token
 #[derive(Clone, Debug, Default, Eq, PartialEq, Serialize)]
 pub struct NetworkToken(StrongSecret<String, CardNumberStrategy>);
@@ -268,6 +272,20 @@ impl Deref for CardNumber {
     }
 }
 
+impl From<CardNumber> for LastFourCardNumber {
+    fn from(card_number: CardNumber) -> Self {
+        Self(StrongSecret::new(card_number.get_card_no()))
+    }
+}
+
+impl Deref for LastFourCardNumber {
+    type Target = StrongSecret<String, WithLastFour>;
+
+    fn deref(&self) -> &StrongSecret<String, WithLastFour> {
+        &self.0
+    }
+}
+
 impl Deref for NetworkToken {
     type Target = StrongSecret<String, CardNumberStrategy>;
 
@@ -283,6 +301,12 @@ impl<'de> Deserialize<'de> for CardNumber {
     }
 }
 
+impl<'de> Deserialize<'de> for LastFourCardNumber {
+    fn deserialize<D: Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+        CardNumber::deserialize(d).map(Self::from)
+    }
+}
+
 impl<'de> Deserialize<'de> for NetworkToken {
     fn deserialize<D: Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
         let s = String::deserialize(d)?;
@@ -25,8 +25,7 @@ use crate::{
 /// A string constant representing a redacted or masked value.
 pub const REDACTED: &str = "Redacted";
 
-/// Type alias for serde_json value which has Secret Information
-pub type SecretSerdeValue = Secret<serde_json::Value>;
+pub use masking::SecretSerdeValue;
 
 /// Strategy for masking a PhoneNumber
 #[derive(Debug)]
@@ -206,6 +205,10 @@ where
 }
 
 /// Strategy for masking Email
+///
+/// Only the first character of the local part and of the domain, and the top level domain are
+/// kept, e.g. `j***@e***.com`. The masked parts are of a fixed length so that the length of the
+/// address isn't revealed either.
 #[derive(Debug, Copy, Clone, Deserialize)]
 pub enum EmailStrategy {}
 
@@ -215,8 +218,19 @@ where
 {
     fn fmt(val: &T, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let val_str: &str = val.as_ref();
+        let first_character = |part: &str| part.chars().next().map(String::from);
         match val_str.split_once('@') {
-            Some((a, b)) => write!(f, "{}@{}", "*".repeat(a.len()), b),
+            Some((local_part, domain)) => {
+                let local_part = first_character(local_part).unwrap_or_default();
+                match domain.rsplit_once('.') {
+                    Some((domain_name, top_level_domain)) => write!(
+                        f,
+                        "{local_part}***@{}***.{top_level_domain}",
+                        first_character(domain_name).unwrap_or_default()
+                    ),
+                    None => write!(f, "{local_part}***@***"),
+                }
+            }
             None => WithType::fmt(val, f),
         }
     }
@@ -395,10 +409,32 @@ mod pii_masking_strategy_tests {
     #[test]
     fn test_valid_email_masking() {
         let secret: Secret<String, EmailStrategy> = Secret::new("example@test.com".to_string());
-        assert_eq!("*******@test.com", format!("{secret:?}"));
+        assert_eq!("e***@t***.com", format!("{secret:?}"));
 
         let secret: Secret<String, EmailStrategy> = Secret::new("username@gmail.com".to_string());
-        assert_eq!("********@gmail.com", format!("{secret:?}"));
+        assert_eq!("u***@g***.com", format!("{secret:?}"));
+
+        let secret: Secret<String, EmailStrategy> =
+            Secret::new("john.doe@mail.example.co.uk".to_string());
+        assert_eq!("j***@m***.uk", format!("{secret:?}"));
+    }
+
+    #[test]
+    fn test_malformed_email_masking() {
+        let secret: Secret<String, EmailStrategy> = Secret::new("@example.com".to_string());
+        assert_eq!("***@e***.com", format!("{secret:?}"));
+
+        let secret: Secret<String, EmailStrategy> = Secret::new("john@localhost".to_string());
+        assert_eq!("j***@***", format!("{secret:?}"));
+
+        let secret: Secret<String, EmailStrategy> = Secret::new("john@".to_string());
+        assert_eq!("j***@***", format!("{secret:?}"));
+
+        let email = Email::from_str("jane@example.com");
+        assert_eq!(
+            email.map(|email| format!("{email:?}")).ok(),
+            Some("Email(j***@e***.com)".to_string())
+        );
     }
 
     #[test]
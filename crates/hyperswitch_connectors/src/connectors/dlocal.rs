@@ -8,50 +8,77 @@ use common_utils::{
     crypto::{self, SignMessage},
     date_time,
     errors::CustomResult,
-    ext_traits::ByteSliceExt,
+    ext_traits::{ByteSliceExt, ValueExt},
     request::{Method, Request, RequestBuilder, RequestContent},
+    types::{AmountConvertor, FloatMajorUnit, FloatMajorUnitForConnector},
 };
-use error_stack::{report, ResultExt};
+use error_stack::ResultExt;
 use hex::encode;
 use hyperswitch_domain_models::{
-    router_data::{AccessToken, ErrorResponse, RouterData},
+    payment_method_data::PaymentMethodData,
+    router_data::{AccessToken, ConnectorAuthType, ErrorResponse, RouterData},
     router_flow_types::{
         access_token_auth::AccessTokenAuth,
-        payments::{Authorize, Capture, PSync, PaymentMethodToken, Session, SetupMandate, Void},
+        dispute::{Accept, Evidence},
+        payments::{
+            Authorize, Capture, CompleteAuthorize, PSync, PaymentMethodToken, Session,
+            SetupMandate, Void,
+        },
         refunds::{Execute, RSync},
     },
     router_request_types::{
-        AccessTokenRequestData, PaymentMethodTokenizationData, PaymentsAuthorizeData,
-        PaymentsCancelData, PaymentsCaptureData, PaymentsSessionData, PaymentsSyncData,
-        RefundsData, SetupMandateRequestData,
+        AcceptDisputeRequestData, AccessTokenRequestData, CompleteAuthorizeData,
+        PaymentMethodTokenizationData, PaymentsAuthorizeData, PaymentsCancelData,
+        PaymentsCaptureData, PaymentsSessionData, PaymentsSyncData, RefundsData,
+        SetupMandateRequestData, SubmitEvidenceRequestData, SyncRequestType,
+    },
+    router_response_types::{
+        AcceptDisputeResponse, PaymentsResponseData, RefundsResponseData, SubmitEvidenceResponse,
     },
-    router_response_types::{PaymentsResponseData, RefundsResponseData},
     types::{
         PaymentsAuthorizeRouterData, PaymentsCancelRouterData, PaymentsCaptureRouterData,
-        PaymentsSyncRouterData, RefundSyncRouterData, RefundsRouterData,
+        PaymentsCompleteAuthorizeRouterData, PaymentsSyncRouterData, RefundSyncRouterData,
+        RefundsRouterData, SetupMandateRouterData,
     },
 };
+#[cfg(feature = "payouts")]
+use hyperswitch_domain_models::{
+    router_flow_types::payouts::{PoCancel, PoCreate, PoFulfill, PoSync},
+    router_response_types::PayoutsResponseData,
+    types::{PayoutsData, PayoutsRouterData},
+};
 use hyperswitch_interfaces::{
     api::{
-        self, ConnectorCommon, ConnectorCommonExt, ConnectorIntegration, ConnectorSpecifications,
-        ConnectorValidation,
+        self, CaptureSyncMethod, ConnectorCommon, ConnectorCommonExt, ConnectorIntegration,
+        ConnectorSpecifications, ConnectorValidation,
     },
     configs::Connectors,
-    errors,
+    disputes, errors,
     events::connector_api_logs::ConnectorEvent,
     types::{self, Response},
     webhooks,
 };
-use masking::{Mask, Maskable, PeekInterface};
+use masking::{Mask, Maskable, PeekInterface, Secret};
 use transformers as dlocal;
 
 use crate::{
     constants::headers,
     types::ResponseRouterData,
-    utils::{self},
+    utils::{self, convert_amount, PaymentMethodDataType},
 };
-#[derive(Debug, Clone)]
-pub struct Dlocal;
+
+#[derive(Clone)]
+pub struct Dlocal {
+    amount_converter: &'static (dyn AmountConvertor<Output = FloatMajorUnit> + Sync),
+}
+
+impl Dlocal {
+    pub fn new() -> &'static Self {
+        &Self {
+            amount_converter: &FloatMajorUnitForConnector,
+        }
+    }
+}
 
 impl api::Payment for Dlocal {}
 impl api::PaymentToken for Dlocal {}
@@ -59,12 +86,25 @@ impl api::PaymentSession for Dlocal {}
 impl api::ConnectorAccessToken for Dlocal {}
 impl api::MandateSetup for Dlocal {}
 impl api::PaymentAuthorize for Dlocal {}
+impl api::PaymentsCompleteAuthorize for Dlocal {}
 impl api::PaymentSync for Dlocal {}
 impl api::PaymentCapture for Dlocal {}
 impl api::PaymentVoid for Dlocal {}
 impl api::Refund for Dlocal {}
 impl api::RefundExecute for Dlocal {}
 impl api::RefundSync for Dlocal {}
+impl api::disputes::Dispute for Dlocal {}
+impl api::disputes::AcceptDispute for Dlocal {}
+impl api::disputes::SubmitEvidence for Dlocal {}
+impl api::Payouts for Dlocal {}
+#[cfg(feature = "payouts")]
+impl api::PayoutCreate for Dlocal {}
+#[cfg(feature = "payouts")]
+impl api::PayoutFulfill for Dlocal {}
+#[cfg(feature = "payouts")]
+impl api::PayoutSync for Dlocal {}
+#[cfg(feature = "payouts")]
+impl api::PayoutCancel for Dlocal {}
 
 impl<Flow, Request, Response> ConnectorCommonExt<Flow, Request, Response> for Dlocal
 where
@@ -108,7 +148,7 @@ where
             (headers::X_DATE.to_string(), date.into()),
             (
                 headers::CONTENT_TYPE.to_string(),
-                Self.get_content_type().to_string().into(),
+                self.get_content_type().to_string().into(),
             ),
         ];
         Ok(headers)
@@ -120,10 +160,6 @@ impl ConnectorCommon for Dlocal {
         "dlocal"
     }
 
-    fn get_currency_unit(&self) -> api::CurrencyUnit {
-        api::CurrencyUnit::Minor
-    }
-
     fn common_get_content_type(&self) -> &'static str {
         "application/json"
     }
@@ -137,6 +173,19 @@ impl ConnectorCommon for Dlocal {
         res: Response,
         event_builder: Option<&mut ConnectorEvent>,
     ) -> CustomResult<ErrorResponse, errors::ConnectorError> {
+        if res.status_code == 429 {
+            let retry_after = res
+                .headers
+                .as_ref()
+                .and_then(|headers| headers.get(headers::RETRY_AFTER))
+                .and_then(|retry_after| retry_after.to_str().ok());
+            router_env::logger::info!(connector_response=?res.response, ?retry_after);
+            return Ok(dlocal::get_rate_limit_error_response(
+                &res.response,
+                retry_after,
+                res.status_code,
+            ));
+        }
         let response: dlocal::DlocalErrorResponse = res
             .response
             .parse_struct("Dlocal ErrorResponse")
@@ -145,14 +194,7 @@ impl ConnectorCommon for Dlocal {
         event_builder.map(|i: &mut ConnectorEvent| i.set_error_response_body(&response));
         router_env::logger::info!(connector_response=?response);
 
-        Ok(ErrorResponse {
-            status_code: res.status_code,
-            code: response.code.to_string(),
-            message: response.message,
-            reason: response.param,
-            attempt_status: None,
-            connector_transaction_id: None,
-        })
+        Ok(response.get_error_response(res.status_code))
     }
 }
 
@@ -167,12 +209,23 @@ impl ConnectorValidation for Dlocal {
         match capture_method {
             enums::CaptureMethod::Automatic
             | enums::CaptureMethod::Manual
+            | enums::CaptureMethod::ManualMultiple
             | enums::CaptureMethod::SequentialAutomatic => Ok(()),
-            enums::CaptureMethod::ManualMultiple | enums::CaptureMethod::Scheduled => Err(
-                utils::construct_not_supported_error_report(capture_method, self.id()),
-            ),
+            enums::CaptureMethod::Scheduled => Err(utils::construct_not_supported_error_report(
+                capture_method,
+                self.id(),
+            )),
         }
     }
+
+    fn validate_mandate_payment(
+        &self,
+        pm_type: Option<enums::PaymentMethodType>,
+        pm_data: PaymentMethodData,
+    ) -> CustomResult<(), errors::ConnectorError> {
+        let mandate_supported_pmd = std::collections::HashSet::from([PaymentMethodDataType::Card]);
+        utils::is_mandate_supported(pm_data, pm_type, mandate_supported_pmd, self.id())
+    }
 }
 
 impl ConnectorIntegration<PaymentMethodToken, PaymentMethodTokenizationData, PaymentsResponseData>
@@ -188,15 +241,89 @@ impl ConnectorIntegration<Session, PaymentsSessionData, PaymentsResponseData> fo
 impl ConnectorIntegration<AccessTokenAuth, AccessTokenRequestData, AccessToken> for Dlocal {}
 
 impl ConnectorIntegration<SetupMandate, SetupMandateRequestData, PaymentsResponseData> for Dlocal {
-    fn build_request(
+    fn get_headers(
+        &self,
+        req: &SetupMandateRouterData,
+        connectors: &Connectors,
+    ) -> CustomResult<Vec<(String, Maskable<String>)>, errors::ConnectorError> {
+        let mut header = self.build_headers(req, connectors)?;
+        let idempotency_key =
+            dlocal::DlocalIdempotencyKey::from_reference_id(&req.connector_request_reference_id)?;
+        header.push((
+            headers::X_IDEMPOTENCY_KEY.to_string(),
+            idempotency_key.get_key().into(),
+        ));
+        Ok(header)
+    }
+
+    fn get_content_type(&self) -> &'static str {
+        self.common_get_content_type()
+    }
+
+    fn get_url(
+        &self,
+        _req: &SetupMandateRouterData,
+        connectors: &Connectors,
+    ) -> CustomResult<String, errors::ConnectorError> {
+        Ok(format!("{}secure_payments", self.base_url(connectors)))
+    }
+
+    fn get_request_body(
         &self,
-        _req: &RouterData<SetupMandate, SetupMandateRequestData, PaymentsResponseData>,
+        req: &SetupMandateRouterData,
         _connectors: &Connectors,
+    ) -> CustomResult<RequestContent, errors::ConnectorError> {
+        let connector_req = dlocal::DlocalPaymentsRequest::try_from(req)?;
+        Ok(RequestContent::Json(Box::new(connector_req)))
+    }
+
+    fn build_request(
+        &self,
+        req: &SetupMandateRouterData,
+        connectors: &Connectors,
     ) -> CustomResult<Option<Request>, errors::ConnectorError> {
-        Err(
-            errors::ConnectorError::NotImplemented("Setup Mandate flow for Dlocal".to_string())
-                .into(),
-        )
+        Ok(Some(
+            RequestBuilder::new()
+                .method(Method::Post)
+                .url(&types::SetupMandateType::get_url(self, req, connectors)?)
+                .attach_default_headers()
+                .headers(types::SetupMandateType::get_headers(self, req, connectors)?)
+                .set_body(types::SetupMandateType::get_request_body(
+                    self, req, connectors,
+                )?)
+                .build(),
+        ))
+    }
+
+    fn handle_response(
+        &self,
+        data: &SetupMandateRouterData,
+        event_builder: Option<&mut ConnectorEvent>,
+        res: Response,
+    ) -> CustomResult<SetupMandateRouterData, errors::ConnectorError> {
+        router_env::logger::debug!(dlocal_setup_mandate_response=?res);
+        let response: dlocal::DlocalSetupMandateResponse = res
+            .response
+            .parse_struct("Dlocal SetupMandateResponse")
+            .change_context(errors::ConnectorError::ResponseDeserializationFailed)?;
+
+        event_builder.map(|i| i.set_response_body(&response));
+        router_env::logger::info!(connector_response=?response);
+
+        RouterData::try_from(ResponseRouterData {
+            response,
+            data: data.clone(),
+            http_code: res.status_code,
+        })
+        .change_context(errors::ConnectorError::ResponseHandlingFailed)
+    }
+
+    fn get_error_response(
+        &self,
+        res: Response,
+        event_builder: Option<&mut ConnectorEvent>,
+    ) -> CustomResult<ErrorResponse, errors::ConnectorError> {
+        self.build_error_response(res, event_builder)
     }
 }
 
@@ -206,7 +333,14 @@ impl ConnectorIntegration<Authorize, PaymentsAuthorizeData, PaymentsResponseData
         req: &PaymentsAuthorizeRouterData,
         connectors: &Connectors,
     ) -> CustomResult<Vec<(String, Maskable<String>)>, errors::ConnectorError> {
-        self.build_headers(req, connectors)
+        let mut header = self.build_headers(req, connectors)?;
+        let idempotency_key =
+            dlocal::DlocalIdempotencyKey::from_reference_id(&req.connector_request_reference_id)?;
+        header.push((
+            headers::X_IDEMPOTENCY_KEY.to_string(),
+            idempotency_key.get_key().into(),
+        ));
+        Ok(header)
     }
 
     fn get_content_type(&self) -> &'static str {
@@ -226,12 +360,12 @@ impl ConnectorIntegration<Authorize, PaymentsAuthorizeData, PaymentsResponseData
         req: &PaymentsAuthorizeRouterData,
         _connectors: &Connectors,
     ) -> CustomResult<RequestContent, errors::ConnectorError> {
-        let connector_router_data = dlocal::DlocalRouterData::try_from((
-            &self.get_currency_unit(),
+        let amount = convert_amount(
+            self.amount_converter,
+            req.request.minor_amount,
             req.request.currency,
-            req.request.amount,
-            req,
-        ))?;
+        )?;
+        let connector_router_data = dlocal::DlocalRouterData::from((amount, req));
         let connector_req = dlocal::DlocalPaymentsRequest::try_from(&connector_router_data)?;
         Ok(RequestContent::Json(Box::new(connector_req)))
     }
@@ -273,11 +407,96 @@ impl ConnectorIntegration<Authorize, PaymentsAuthorizeData, PaymentsResponseData
         event_builder.map(|i| i.set_response_body(&response));
         router_env::logger::info!(connector_response=?response);
 
-        RouterData::try_from(ResponseRouterData {
-            response,
-            data: data.clone(),
-            http_code: res.status_code,
-        })
+        RouterData::try_from((
+            ResponseRouterData {
+                response,
+                data: data.clone(),
+                http_code: res.status_code,
+            },
+            data.request.setup_future_usage,
+        ))
+        .change_context(errors::ConnectorError::ResponseHandlingFailed)
+    }
+
+    fn get_error_response(
+        &self,
+        res: Response,
+        event_builder: Option<&mut ConnectorEvent>,
+    ) -> CustomResult<ErrorResponse, errors::ConnectorError> {
+        self.build_error_response(res, event_builder)
+    }
+}
+
+impl ConnectorIntegration<CompleteAuthorize, CompleteAuthorizeData, PaymentsResponseData>
+    for Dlocal
+{
+    fn get_headers(
+        &self,
+        req: &PaymentsCompleteAuthorizeRouterData,
+        connectors: &Connectors,
+    ) -> CustomResult<Vec<(String, Maskable<String>)>, errors::ConnectorError> {
+        self.build_headers(req, connectors)
+    }
+
+    fn get_content_type(&self) -> &'static str {
+        self.common_get_content_type()
+    }
+
+    fn get_url(
+        &self,
+        req: &PaymentsCompleteAuthorizeRouterData,
+        connectors: &Connectors,
+    ) -> CustomResult<String, errors::ConnectorError> {
+        let complete_authorize_data = dlocal::DlocalCompleteAuthorizeRequest::try_from(req)?;
+        Ok(format!(
+            "{}payments/{}",
+            self.base_url(connectors),
+            complete_authorize_data.payment_id,
+        ))
+    }
+
+    fn build_request(
+        &self,
+        req: &PaymentsCompleteAuthorizeRouterData,
+        connectors: &Connectors,
+    ) -> CustomResult<Option<Request>, errors::ConnectorError> {
+        Ok(Some(
+            RequestBuilder::new()
+                .method(Method::Get)
+                .url(&types::PaymentsCompleteAuthorizeType::get_url(
+                    self, req, connectors,
+                )?)
+                .attach_default_headers()
+                .headers(types::PaymentsCompleteAuthorizeType::get_headers(
+                    self, req, connectors,
+                )?)
+                .build(),
+        ))
+    }
+
+    fn handle_response(
+        &self,
+        data: &PaymentsCompleteAuthorizeRouterData,
+        event_builder: Option<&mut ConnectorEvent>,
+        res: Response,
+    ) -> CustomResult<PaymentsCompleteAuthorizeRouterData, errors::ConnectorError> {
+        router_env::logger::debug!(dlocal_complete_authorize_response=?res);
+        let response: dlocal::DlocalCompleteAuthorizeResponse = res
+            .response
+            .parse_struct("Dlocal CompleteAuthorizeResponse")
+            .change_context(errors::ConnectorError::ResponseDeserializationFailed)?;
+
+        event_builder.map(|i| i.set_response_body(&response));
+        router_env::logger::info!(connector_response=?response);
+
+        RouterData::try_from((
+            ResponseRouterData {
+                response,
+                data: data.clone(),
+                http_code: res.status_code,
+            },
+            data.request.setup_future_usage,
+        ))
         .change_context(errors::ConnectorError::ResponseHandlingFailed)
     }
 
@@ -352,6 +571,18 @@ impl ConnectorIntegration<PSync, PaymentsSyncData, PaymentsResponseData> for Dlo
             .change_context(errors::ConnectorError::ResponseDeserializationFailed)?;
         event_builder.map(|i| i.set_response_body(&response));
         router_env::logger::info!(connector_response=?response);
+        // Captures are synced one at a time, the capture id is the id of the payment dLocal
+        // created for it
+        if let SyncRequestType::MultipleCaptureSync(_) = data.request.sync_type {
+            let capture_sync_response_list =
+                utils::construct_captures_response_hashmap(vec![response])?;
+            return Ok(PaymentsSyncRouterData {
+                response: Ok(PaymentsResponseData::MultipleCaptureResponse {
+                    capture_sync_response_list,
+                }),
+                ..data.clone()
+            });
+        }
         RouterData::try_from(ResponseRouterData {
             response,
             data: data.clone(),
@@ -359,6 +590,12 @@ impl ConnectorIntegration<PSync, PaymentsSyncData, PaymentsResponseData> for Dlo
         })
         .change_context(errors::ConnectorError::ResponseHandlingFailed)
     }
+
+    fn get_multiple_capture_sync_method(
+        &self,
+    ) -> CustomResult<CaptureSyncMethod, errors::ConnectorError> {
+        Ok(CaptureSyncMethod::Individual)
+    }
 }
 
 impl ConnectorIntegration<Capture, PaymentsCaptureData, PaymentsResponseData> for Dlocal {
@@ -387,7 +624,13 @@ impl ConnectorIntegration<Capture, PaymentsCaptureData, PaymentsResponseData> fo
         req: &PaymentsCaptureRouterData,
         _connectors: &Connectors,
     ) -> CustomResult<RequestContent, errors::ConnectorError> {
-        let connector_req = dlocal::DlocalPaymentsCaptureRequest::try_from(req)?;
+        let amount = convert_amount(
+            self.amount_converter,
+            req.request.minor_amount_to_capture,
+            req.request.currency,
+        )?;
+        let connector_router_data = dlocal::DlocalRouterData::from((amount, req));
+        let connector_req = dlocal::DlocalPaymentsCaptureRequest::try_from(&connector_router_data)?;
         Ok(RequestContent::Json(Box::new(connector_req)))
     }
 
@@ -418,7 +661,7 @@ impl ConnectorIntegration<Capture, PaymentsCaptureData, PaymentsResponseData> fo
         res: Response,
     ) -> CustomResult<PaymentsCaptureRouterData, errors::ConnectorError> {
         router_env::logger::debug!(dlocal_payments_capture_response=?res);
-        let response: dlocal::DlocalPaymentsResponse = res
+        let response: dlocal::DlocalPaymentsCaptureResponse = res
             .response
             .parse_struct("Dlocal PaymentsCaptureResponse")
             .change_context(errors::ConnectorError::ResponseDeserializationFailed)?;
@@ -459,14 +702,28 @@ impl ConnectorIntegration<Void, PaymentsCancelData, PaymentsResponseData> for Dl
         req: &PaymentsCancelRouterData,
         connectors: &Connectors,
     ) -> CustomResult<String, errors::ConnectorError> {
-        let cancel_data = dlocal::DlocalPaymentsCancelRequest::try_from(req)?;
         Ok(format!(
             "{}payments/{}/cancel",
             self.base_url(connectors),
-            cancel_data.cancel_id
+            req.request.connector_transaction_id
         ))
     }
 
+    fn get_request_body(
+        &self,
+        req: &PaymentsCancelRouterData,
+        _connectors: &Connectors,
+    ) -> CustomResult<RequestContent, errors::ConnectorError> {
+        let amount = req
+            .request
+            .minor_amount
+            .zip(req.request.currency)
+            .map(|(amount, currency)| convert_amount(self.amount_converter, amount, currency))
+            .transpose()?;
+        let connector_req = dlocal::DlocalPaymentsCancelRequest::try_from((amount, req))?;
+        Ok(RequestContent::Json(Box::new(connector_req)))
+    }
+
     fn build_request(
         &self,
         req: &PaymentsCancelRouterData,
@@ -478,6 +735,9 @@ impl ConnectorIntegration<Void, PaymentsCancelData, PaymentsResponseData> for Dl
                 .url(&types::PaymentsVoidType::get_url(self, req, connectors)?)
                 .attach_default_headers()
                 .headers(types::PaymentsVoidType::get_headers(self, req, connectors)?)
+                .set_body(types::PaymentsVoidType::get_request_body(
+                    self, req, connectors,
+                )?)
                 .build(),
         ))
     }
@@ -489,7 +749,7 @@ impl ConnectorIntegration<Void, PaymentsCancelData, PaymentsResponseData> for Dl
         res: Response,
     ) -> CustomResult<PaymentsCancelRouterData, errors::ConnectorError> {
         router_env::logger::debug!(dlocal_payments_cancel_response=?res);
-        let response: dlocal::DlocalPaymentsResponse = res
+        let response: dlocal::DlocalPaymentsCancelResponse = res
             .response
             .parse_struct("Dlocal PaymentsCancelResponse")
             .change_context(errors::ConnectorError::ResponseDeserializationFailed)?;
@@ -539,12 +799,12 @@ impl ConnectorIntegration<Execute, RefundsData, RefundsResponseData> for Dlocal
         req: &RefundsRouterData<Execute>,
         _connectors: &Connectors,
     ) -> CustomResult<RequestContent, errors::ConnectorError> {
-        let connector_router_data = dlocal::DlocalRouterData::try_from((
-            &self.get_currency_unit(),
+        let amount = convert_amount(
+            self.amount_converter,
+            req.request.minor_refund_amount,
             req.request.currency,
-            req.request.refund_amount,
-            req,
-        ))?;
+        )?;
+        let connector_router_data = dlocal::DlocalRouterData::from((amount, req));
         let connector_req = dlocal::DlocalRefundRequest::try_from(&connector_router_data)?;
         Ok(RequestContent::Json(Box::new(connector_req)))
     }
@@ -669,27 +929,605 @@ impl ConnectorIntegration<RSync, RefundsData, RefundsResponseData> for Dlocal {
     }
 }
 
-#[async_trait::async_trait]
-impl webhooks::IncomingWebhook for Dlocal {
-    fn get_webhook_object_reference_id(
+impl ConnectorIntegration<Accept, AcceptDisputeRequestData, AcceptDisputeResponse> for Dlocal {
+    fn get_headers(
         &self,
-        _request: &webhooks::IncomingWebhookRequestDetails<'_>,
-    ) -> CustomResult<api_models::webhooks::ObjectReferenceId, errors::ConnectorError> {
-        Err(report!(errors::ConnectorError::WebhooksNotImplemented))
+        req: &RouterData<Accept, AcceptDisputeRequestData, AcceptDisputeResponse>,
+        connectors: &Connectors,
+    ) -> CustomResult<Vec<(String, Maskable<String>)>, errors::ConnectorError> {
+        self.build_headers(req, connectors)
     }
 
-    fn get_webhook_event_type(
+    fn get_content_type(&self) -> &'static str {
+        self.common_get_content_type()
+    }
+
+    fn get_url(
         &self,
-        _request: &webhooks::IncomingWebhookRequestDetails<'_>,
-    ) -> CustomResult<IncomingWebhookEvent, errors::ConnectorError> {
-        Ok(IncomingWebhookEvent::EventNotSupported)
+        req: &RouterData<Accept, AcceptDisputeRequestData, AcceptDisputeResponse>,
+        connectors: &Connectors,
+    ) -> CustomResult<String, errors::ConnectorError> {
+        Ok(format!(
+            "{}chargebacks/{}/accept",
+            self.base_url(connectors),
+            req.request.connector_dispute_id,
+        ))
     }
 
-    fn get_webhook_resource_object(
+    fn get_request_body(
         &self,
-        _request: &webhooks::IncomingWebhookRequestDetails<'_>,
-    ) -> CustomResult<Box<dyn masking::ErasedMaskSerialize>, errors::ConnectorError> {
-        Err(report!(errors::ConnectorError::WebhooksNotImplemented))
+        req: &RouterData<Accept, AcceptDisputeRequestData, AcceptDisputeResponse>,
+        _connectors: &Connectors,
+    ) -> CustomResult<RequestContent, errors::ConnectorError> {
+        let connector_req = dlocal::DlocalAcceptDisputeRequest::try_from(req)?;
+        Ok(RequestContent::Json(Box::new(connector_req)))
+    }
+
+    fn build_request(
+        &self,
+        req: &RouterData<Accept, AcceptDisputeRequestData, AcceptDisputeResponse>,
+        connectors: &Connectors,
+    ) -> CustomResult<Option<Request>, errors::ConnectorError> {
+        Ok(Some(
+            RequestBuilder::new()
+                .method(Method::Post)
+                .url(&types::AcceptDisputeType::get_url(self, req, connectors)?)
+                .attach_default_headers()
+                .headers(types::AcceptDisputeType::get_headers(
+                    self, req, connectors,
+                )?)
+                .set_body(types::AcceptDisputeType::get_request_body(
+                    self, req, connectors,
+                )?)
+                .build(),
+        ))
+    }
+
+    fn handle_response(
+        &self,
+        data: &RouterData<Accept, AcceptDisputeRequestData, AcceptDisputeResponse>,
+        event_builder: Option<&mut ConnectorEvent>,
+        res: Response,
+    ) -> CustomResult<
+        RouterData<Accept, AcceptDisputeRequestData, AcceptDisputeResponse>,
+        errors::ConnectorError,
+    > {
+        let response: dlocal::DlocalAcceptDisputeResponse = res
+            .response
+            .parse_struct("Dlocal AcceptDisputeResponse")
+            .change_context(errors::ConnectorError::ResponseDeserializationFailed)?;
+        event_builder.map(|i| i.set_response_body(&response));
+        router_env::logger::info!(connector_response=?response);
+        RouterData::try_from(ResponseRouterData {
+            response,
+            data: data.clone(),
+            http_code: res.status_code,
+        })
+    }
+
+    fn get_error_response(
+        &self,
+        res: Response,
+        event_builder: Option<&mut ConnectorEvent>,
+    ) -> CustomResult<ErrorResponse, errors::ConnectorError> {
+        self.build_error_response(res, event_builder)
+    }
+}
+
+impl ConnectorIntegration<Evidence, SubmitEvidenceRequestData, SubmitEvidenceResponse> for Dlocal {
+    fn get_headers(
+        &self,
+        req: &RouterData<Evidence, SubmitEvidenceRequestData, SubmitEvidenceResponse>,
+        connectors: &Connectors,
+    ) -> CustomResult<Vec<(String, Maskable<String>)>, errors::ConnectorError> {
+        self.build_headers(req, connectors)
+    }
+
+    fn get_content_type(&self) -> &'static str {
+        self.common_get_content_type()
+    }
+
+    fn get_url(
+        &self,
+        req: &RouterData<Evidence, SubmitEvidenceRequestData, SubmitEvidenceResponse>,
+        connectors: &Connectors,
+    ) -> CustomResult<String, errors::ConnectorError> {
+        Ok(format!(
+            "{}chargebacks/{}/dispute",
+            self.base_url(connectors),
+            req.request.connector_dispute_id,
+        ))
+    }
+
+    fn get_request_body(
+        &self,
+        req: &RouterData<Evidence, SubmitEvidenceRequestData, SubmitEvidenceResponse>,
+        _connectors: &Connectors,
+    ) -> CustomResult<RequestContent, errors::ConnectorError> {
+        let connector_req = dlocal::DlocalSubmitEvidenceRequest::try_from(req)?;
+        Ok(RequestContent::Json(Box::new(connector_req)))
+    }
+
+    fn build_request(
+        &self,
+        req: &RouterData<Evidence, SubmitEvidenceRequestData, SubmitEvidenceResponse>,
+        connectors: &Connectors,
+    ) -> CustomResult<Option<Request>, errors::ConnectorError> {
+        Ok(Some(
+            RequestBuilder::new()
+                .method(Method::Post)
+                .url(&types::SubmitEvidenceType::get_url(self, req, connectors)?)
+                .attach_default_headers()
+                .headers(types::SubmitEvidenceType::get_headers(
+                    self, req, connectors,
+                )?)
+                .set_body(types::SubmitEvidenceType::get_request_body(
+                    self, req, connectors,
+                )?)
+                .build(),
+        ))
+    }
+
+    fn handle_response(
+        &self,
+        data: &RouterData<Evidence, SubmitEvidenceRequestData, SubmitEvidenceResponse>,
+        event_builder: Option<&mut ConnectorEvent>,
+        res: Response,
+    ) -> CustomResult<
+        RouterData<Evidence, SubmitEvidenceRequestData, SubmitEvidenceResponse>,
+        errors::ConnectorError,
+    > {
+        let response: dlocal::DlocalDisputeResponse = res
+            .response
+            .parse_struct("Dlocal SubmitEvidenceResponse")
+            .change_context(errors::ConnectorError::ResponseDeserializationFailed)?;
+        event_builder.map(|i| i.set_response_body(&response));
+        router_env::logger::info!(connector_response=?response);
+        RouterData::try_from(ResponseRouterData {
+            response,
+            data: data.clone(),
+            http_code: res.status_code,
+        })
+    }
+
+    fn get_error_response(
+        &self,
+        res: Response,
+        event_builder: Option<&mut ConnectorEvent>,
+    ) -> CustomResult<ErrorResponse, errors::ConnectorError> {
+        self.build_error_response(res, event_builder)
+            .map(dlocal::get_submit_evidence_error_response)
+    }
+}
+
+#[cfg(feature = "payouts")]
+impl ConnectorIntegration<PoCreate, PayoutsData, PayoutsResponseData> for Dlocal {
+    fn get_headers(
+        &self,
+        req: &PayoutsRouterData<PoCreate>,
+        connectors: &Connectors,
+    ) -> CustomResult<Vec<(String, Maskable<String>)>, errors::ConnectorError> {
+        self.build_headers(req, connectors)
+    }
+
+    fn get_content_type(&self) -> &'static str {
+        self.common_get_content_type()
+    }
+
+    fn get_url(
+        &self,
+        _req: &PayoutsRouterData<PoCreate>,
+        connectors: &Connectors,
+    ) -> CustomResult<String, errors::ConnectorError> {
+        Ok(format!("{}payouts", self.base_url(connectors)))
+    }
+
+    fn get_request_body(
+        &self,
+        req: &PayoutsRouterData<PoCreate>,
+        _connectors: &Connectors,
+    ) -> CustomResult<RequestContent, errors::ConnectorError> {
+        let amount = convert_amount(
+            self.amount_converter,
+            req.request.minor_amount,
+            req.request.destination_currency,
+        )?;
+        let connector_router_data = dlocal::DlocalRouterData::from((amount, req));
+        let connector_req = dlocal::DlocalPayoutCreateRequest::try_from(&connector_router_data)?;
+        Ok(RequestContent::Json(Box::new(connector_req)))
+    }
+
+    fn build_request(
+        &self,
+        req: &PayoutsRouterData<PoCreate>,
+        connectors: &Connectors,
+    ) -> CustomResult<Option<Request>, errors::ConnectorError> {
+        Ok(Some(
+            RequestBuilder::new()
+                .method(Method::Post)
+                .url(&types::PayoutCreateType::get_url(self, req, connectors)?)
+                .attach_default_headers()
+                .headers(types::PayoutCreateType::get_headers(self, req, connectors)?)
+                .set_body(types::PayoutCreateType::get_request_body(
+                    self, req, connectors,
+                )?)
+                .build(),
+        ))
+    }
+
+    fn handle_response(
+        &self,
+        data: &PayoutsRouterData<PoCreate>,
+        event_builder: Option<&mut ConnectorEvent>,
+        res: Response,
+    ) -> CustomResult<PayoutsRouterData<PoCreate>, errors::ConnectorError> {
+        router_env::logger::debug!(dlocal_payout_create_response=?res);
+        let response: dlocal::DlocalPayoutResponse = res
+            .response
+            .parse_struct("Dlocal PayoutCreateResponse")
+            .change_context(errors::ConnectorError::ResponseDeserializationFailed)?;
+        event_builder.map(|i| i.set_response_body(&response));
+        router_env::logger::info!(connector_response=?response);
+        RouterData::try_from(ResponseRouterData {
+            response,
+            data: data.clone(),
+            http_code: res.status_code,
+        })
+    }
+
+    fn get_error_response(
+        &self,
+        res: Response,
+        event_builder: Option<&mut ConnectorEvent>,
+    ) -> CustomResult<ErrorResponse, errors::ConnectorError> {
+        self.build_error_response(res, event_builder)
+    }
+}
+
+#[cfg(feature = "payouts")]
+impl ConnectorIntegration<PoFulfill, PayoutsData, PayoutsResponseData> for Dlocal {
+    fn get_headers(
+        &self,
+        req: &PayoutsRouterData<PoFulfill>,
+        connectors: &Connectors,
+    ) -> CustomResult<Vec<(String, Maskable<String>)>, errors::ConnectorError> {
+        self.build_headers(req, connectors)
+    }
+
+    fn get_content_type(&self) -> &'static str {
+        self.common_get_content_type()
+    }
+
+    fn get_url(
+        &self,
+        req: &PayoutsRouterData<PoFulfill>,
+        connectors: &Connectors,
+    ) -> CustomResult<String, errors::ConnectorError> {
+        let payout_id = req.request.connector_payout_id.clone().ok_or(
+            errors::ConnectorError::MissingRequiredField {
+                field_name: "connector_payout_id",
+            },
+        )?;
+        Ok(format!(
+            "{}payouts/{}/confirm",
+            self.base_url(connectors),
+            payout_id
+        ))
+    }
+
+    fn get_request_body(
+        &self,
+        req: &PayoutsRouterData<PoFulfill>,
+        _connectors: &Connectors,
+    ) -> CustomResult<RequestContent, errors::ConnectorError> {
+        let connector_req = dlocal::DlocalPayoutFulfillRequest::try_from(req)?;
+        Ok(RequestContent::Json(Box::new(connector_req)))
+    }
+
+    fn build_request(
+        &self,
+        req: &PayoutsRouterData<PoFulfill>,
+        connectors: &Connectors,
+    ) -> CustomResult<Option<Request>, errors::ConnectorError> {
+        Ok(Some(
+            RequestBuilder::new()
+                .method(Method::Post)
+                .url(&types::PayoutFulfillType::get_url(self, req, connectors)?)
+                .attach_default_headers()
+                .headers(types::PayoutFulfillType::get_headers(
+                    self, req, connectors,
+                )?)
+                .set_body(types::PayoutFulfillType::get_request_body(
+                    self, req, connectors,
+                )?)
+                .build(),
+        ))
+    }
+
+    fn handle_response(
+        &self,
+        data: &PayoutsRouterData<PoFulfill>,
+        event_builder: Option<&mut ConnectorEvent>,
+        res: Response,
+    ) -> CustomResult<PayoutsRouterData<PoFulfill>, errors::ConnectorError> {
+        router_env::logger::debug!(dlocal_payout_fulfill_response=?res);
+        let response: dlocal::DlocalPayoutResponse = res
+            .response
+            .parse_struct("Dlocal PayoutFulfillResponse")
+            .change_context(errors::ConnectorError::ResponseDeserializationFailed)?;
+        event_builder.map(|i| i.set_response_body(&response));
+        router_env::logger::info!(connector_response=?response);
+        RouterData::try_from(ResponseRouterData {
+            response,
+            data: data.clone(),
+            http_code: res.status_code,
+        })
+    }
+
+    fn get_error_response(
+        &self,
+        res: Response,
+        event_builder: Option<&mut ConnectorEvent>,
+    ) -> CustomResult<ErrorResponse, errors::ConnectorError> {
+        self.build_error_response(res, event_builder)
+    }
+}
+
+#[cfg(feature = "payouts")]
+impl ConnectorIntegration<PoSync, PayoutsData, PayoutsResponseData> for Dlocal {
+    fn get_headers(
+        &self,
+        req: &PayoutsRouterData<PoSync>,
+        connectors: &Connectors,
+    ) -> CustomResult<Vec<(String, Maskable<String>)>, errors::ConnectorError> {
+        self.build_headers(req, connectors)
+    }
+
+    fn get_content_type(&self) -> &'static str {
+        self.common_get_content_type()
+    }
+
+    fn get_url(
+        &self,
+        req: &PayoutsRouterData<PoSync>,
+        connectors: &Connectors,
+    ) -> CustomResult<String, errors::ConnectorError> {
+        let sync_data = dlocal::DlocalPayoutSyncRequest::try_from(req)?;
+        Ok(format!(
+            "{}payouts/{}",
+            self.base_url(connectors),
+            sync_data.payout_id,
+        ))
+    }
+
+    fn build_request(
+        &self,
+        req: &PayoutsRouterData<PoSync>,
+        connectors: &Connectors,
+    ) -> CustomResult<Option<Request>, errors::ConnectorError> {
+        Ok(Some(
+            RequestBuilder::new()
+                .method(Method::Get)
+                .url(&types::PayoutSyncType::get_url(self, req, connectors)?)
+                .attach_default_headers()
+                .headers(types::PayoutSyncType::get_headers(self, req, connectors)?)
+                .build(),
+        ))
+    }
+
+    fn handle_response(
+        &self,
+        data: &PayoutsRouterData<PoSync>,
+        event_builder: Option<&mut ConnectorEvent>,
+        res: Response,
+    ) -> CustomResult<PayoutsRouterData<PoSync>, errors::ConnectorError> {
+        router_env::logger::debug!(dlocal_payout_sync_response=?res);
+        let response: dlocal::DlocalPayoutResponse = res
+            .response
+            .parse_struct("Dlocal PayoutSyncResponse")
+            .change_context(errors::ConnectorError::ResponseDeserializationFailed)?;
+        event_builder.map(|i| i.set_response_body(&response));
+        router_env::logger::info!(connector_response=?response);
+        RouterData::try_from(ResponseRouterData {
+            response,
+            data: data.clone(),
+            http_code: res.status_code,
+        })
+    }
+
+    fn get_error_response(
+        &self,
+        res: Response,
+        event_builder: Option<&mut ConnectorEvent>,
+    ) -> CustomResult<ErrorResponse, errors::ConnectorError> {
+        self.build_error_response(res, event_builder)
+    }
+}
+
+#[cfg(feature = "payouts")]
+impl ConnectorIntegration<PoCancel, PayoutsData, PayoutsResponseData> for Dlocal {
+    fn get_headers(
+        &self,
+        req: &PayoutsRouterData<PoCancel>,
+        connectors: &Connectors,
+    ) -> CustomResult<Vec<(String, Maskable<String>)>, errors::ConnectorError> {
+        self.build_headers(req, connectors)
+    }
+
+    fn get_content_type(&self) -> &'static str {
+        self.common_get_content_type()
+    }
+
+    fn get_url(
+        &self,
+        req: &PayoutsRouterData<PoCancel>,
+        connectors: &Connectors,
+    ) -> CustomResult<String, errors::ConnectorError> {
+        let cancel_data = dlocal::DlocalPayoutCancelRequest::try_from(req)?;
+        Ok(format!(
+            "{}payouts/{}/cancel",
+            self.base_url(connectors),
+            cancel_data.payout_id,
+        ))
+    }
+
+    fn get_request_body(
+        &self,
+        req: &PayoutsRouterData<PoCancel>,
+        _connectors: &Connectors,
+    ) -> CustomResult<RequestContent, errors::ConnectorError> {
+        let connector_req = dlocal::DlocalPayoutCancelRequest::try_from(req)?;
+        Ok(RequestContent::Json(Box::new(connector_req)))
+    }
+
+    fn build_request(
+        &self,
+        req: &PayoutsRouterData<PoCancel>,
+        connectors: &Connectors,
+    ) -> CustomResult<Option<Request>, errors::ConnectorError> {
+        Ok(Some(
+            RequestBuilder::new()
+                .method(Method::Post)
+                .url(&types::PayoutCancelType::get_url(self, req, connectors)?)
+                .attach_default_headers()
+                .headers(types::PayoutCancelType::get_headers(self, req, connectors)?)
+                .set_body(types::PayoutCancelType::get_request_body(
+                    self, req, connectors,
+                )?)
+                .build(),
+        ))
+    }
+
+    fn handle_response(
+        &self,
+        data: &PayoutsRouterData<PoCancel>,
+        event_builder: Option<&mut ConnectorEvent>,
+        res: Response,
+    ) -> CustomResult<PayoutsRouterData<PoCancel>, errors::ConnectorError> {
+        router_env::logger::debug!(dlocal_payout_cancel_response=?res);
+        let response: dlocal::DlocalPayoutResponse = res
+            .response
+            .parse_struct("Dlocal PayoutCancelResponse")
+            .change_context(errors::ConnectorError::ResponseDeserializationFailed)?;
+        event_builder.map(|i| i.set_response_body(&response));
+        router_env::logger::info!(connector_response=?response);
+        RouterData::try_from(ResponseRouterData {
+            response,
+            data: data.clone(),
+            http_code: res.status_code,
+        })
+    }
+
+    fn get_error_response(
+        &self,
+        res: Response,
+        event_builder: Option<&mut ConnectorEvent>,
+    ) -> CustomResult<ErrorResponse, errors::ConnectorError> {
+        self.build_error_response(res, event_builder)
+    }
+}
+
+#[async_trait::async_trait]
+impl webhooks::IncomingWebhook for Dlocal {
+    async fn verify_webhook_source(
+        &self,
+        request: &webhooks::IncomingWebhookRequestDetails<'_>,
+        _merchant_id: &common_utils::id_type::MerchantId,
+        _connector_webhook_details: Option<common_utils::pii::SecretSerdeValue>,
+        connector_account_details: crypto::Encryptable<Secret<serde_json::Value>>,
+        _connector_label: &str,
+    ) -> CustomResult<bool, errors::ConnectorError> {
+        // Notifications are signed with the account's secret key rather than a webhook secret
+        let connector_account_details = connector_account_details
+            .parse_value::<ConnectorAuthType>("ConnectorAuthType")
+            .change_context(errors::ConnectorError::WebhookSourceVerificationFailed)?;
+        let auth = dlocal::DlocalAuthType::try_from(&connector_account_details)
+            .change_context(errors::ConnectorError::WebhookSourceVerificationFailed)?;
+        let x_date = utils::get_header_key_value(headers::X_DATE, request.headers)
+            .change_context(errors::ConnectorError::WebhookSignatureNotFound)?;
+        let authorization = utils::get_header_key_value(headers::AUTHORIZATION, request.headers)
+            .change_context(errors::ConnectorError::WebhookSignatureNotFound)?;
+        dlocal::verify_webhook_signature(&auth, x_date, authorization, request.body)
+    }
+
+    fn get_webhook_object_reference_id(
+        &self,
+        request: &webhooks::IncomingWebhookRequestDetails<'_>,
+    ) -> CustomResult<api_models::webhooks::ObjectReferenceId, errors::ConnectorError> {
+        let webhook_object: dlocal::DlocalWebhookObject = request
+            .body
+            .parse_struct("DlocalWebhookObject")
+            .change_context(errors::ConnectorError::WebhookReferenceIdNotFound)?;
+        match webhook_object {
+            dlocal::DlocalWebhookObject::Payment(webhook_body) => {
+                Ok(api_models::webhooks::ObjectReferenceId::PaymentId(
+                    api_models::payments::PaymentIdType::ConnectorTransactionId(
+                        webhook_body.payment_id,
+                    ),
+                ))
+            }
+            // Chargebacks are disputes of the payment they were raised against
+            dlocal::DlocalWebhookObject::Chargeback(webhook_body) => {
+                Ok(api_models::webhooks::ObjectReferenceId::PaymentId(
+                    api_models::payments::PaymentIdType::ConnectorTransactionId(
+                        webhook_body.payment_id,
+                    ),
+                ))
+            }
+            dlocal::DlocalWebhookObject::Refund(webhook_body) => {
+                Ok(api_models::webhooks::ObjectReferenceId::RefundId(
+                    api_models::webhooks::RefundIdType::ConnectorRefundId(webhook_body.refund.id),
+                ))
+            }
+        }
+    }
+
+    fn get_webhook_event_type(
+        &self,
+        request: &webhooks::IncomingWebhookRequestDetails<'_>,
+    ) -> CustomResult<IncomingWebhookEvent, errors::ConnectorError> {
+        // Notifications with a status we don't know of are acknowledged and ignored
+        let event = request
+            .body
+            .parse_struct::<dlocal::DlocalWebhookObject>("DlocalWebhookObject")
+            .map(|webhook_object| IncomingWebhookEvent::from(&webhook_object))
+            .unwrap_or_else(|error| {
+                router_env::logger::warn!(dlocal_webhook_body_error=?error);
+                IncomingWebhookEvent::EventNotSupported
+            });
+        Ok(event)
+    }
+
+    fn get_webhook_resource_object(
+        &self,
+        request: &webhooks::IncomingWebhookRequestDetails<'_>,
+    ) -> CustomResult<Box<dyn masking::ErasedMaskSerialize>, errors::ConnectorError> {
+        let webhook_object: dlocal::DlocalWebhookObject = request
+            .body
+            .parse_struct("DlocalWebhookObject")
+            .change_context(errors::ConnectorError::WebhookResourceObjectNotFound)?;
+        match webhook_object {
+            dlocal::DlocalWebhookObject::Payment(_) => {
+                let sync_response: dlocal::DlocalPaymentsResponse = request
+                    .body
+                    .parse_struct("DlocalPaymentsResponse")
+                    .change_context(errors::ConnectorError::WebhookResourceObjectNotFound)?;
+                Ok(Box::new(sync_response))
+            }
+            dlocal::DlocalWebhookObject::Refund(webhook_body) => Ok(Box::new(webhook_body.refund)),
+            dlocal::DlocalWebhookObject::Chargeback(webhook_body) => Ok(Box::new(webhook_body)),
+        }
+    }
+
+    fn get_dispute_details(
+        &self,
+        request: &webhooks::IncomingWebhookRequestDetails<'_>,
+    ) -> CustomResult<disputes::DisputePayload, errors::ConnectorError> {
+        let webhook_body: dlocal::DlocalChargebackWebhookBody = request
+            .body
+            .parse_struct("DlocalChargebackWebhookBody")
+            .change_context(errors::ConnectorError::WebhookBodyDecodingFailed)?;
+        disputes::DisputePayload::try_from(&webhook_body)
     }
 }
 
@@ -1,53 +1,269 @@
+use std::{collections::HashMap, str::FromStr};
+
+#[cfg(feature = "payouts")]
+use api_models::payouts::{Bank, PayoutMethodData};
+use api_models::{payments::VoucherNextStepData, webhooks::IncomingWebhookEvent};
 use common_enums::enums;
-use common_utils::{pii::Email, request::Method};
+use common_utils::{
+    crypto::{self, GenerateDigest, VerifySignature},
+    date_time,
+    errors::{CustomResult, ParsingError},
+    ext_traits::Encode,
+    pii::{self, Email, UpiVpaMaskingStrategy},
+    request::Method,
+    types::{AmountConvertor, FloatMajorUnit, FloatMajorUnitForConnector, MinorUnit},
+};
 use error_stack::ResultExt;
 use hyperswitch_domain_models::{
-    payment_method_data::PaymentMethodData,
-    router_data::{ConnectorAuthType, RouterData},
-    router_flow_types::{refunds::Execute, RSync},
-    router_request_types::ResponseId,
-    router_response_types::{PaymentsResponseData, RedirectForm, RefundsResponseData},
+    address::PhoneDetails,
+    network_tokenization::NetworkTokenNumber,
+    payment_method_data::{BankTransferData, PaymentMethodData, UpiData, VoucherData, WalletData},
+    router_data::{ConnectorAuthType, ErrorResponse, PaymentMethodToken, RouterData},
+    router_flow_types::{
+        dispute::{Accept, Evidence},
+        refunds::Execute,
+        RSync, SetupMandate,
+    },
+    router_request_types::{
+        AcceptDisputeRequestData, AuthenticationData, BrowserInformation, RefundIntegrityObject,
+        ResponseId, SetupMandateRequestData, SubmitEvidenceRequestData,
+    },
+    router_response_types::{
+        AcceptDisputeResponse, MandateReference, PaymentsResponseData, RedirectForm,
+        RefundsResponseData, SubmitEvidenceResponse,
+    },
     types,
 };
-use hyperswitch_interfaces::{api::CurrencyUnit, errors};
-use masking::{PeekInterface, Secret};
+#[cfg(feature = "payouts")]
+use hyperswitch_domain_models::{
+    router_flow_types::payouts::{PoCancel, PoCreate, PoFulfill, PoSync},
+    router_response_types::PayoutsResponseData,
+    types::PayoutsRouterData,
+};
+use hyperswitch_interfaces::{consts, disputes::DisputePayload, errors};
+use masking::{ExposeInterface, PeekInterface, Secret};
 use serde::{Deserialize, Serialize};
+use time::format_description::well_known::Rfc3339;
 use url::Url;
 
+#[cfg(feature = "payouts")]
+use crate::types::PayoutsResponseRouterData;
 use crate::{
-    types::{RefundsResponseRouterData, ResponseRouterData},
-    utils::{AddressDetailsData, PaymentsAuthorizeRequestData, RouterData as _},
+    types::{
+        PaymentsCancelResponseRouterData, PaymentsCaptureResponseRouterData,
+        RefundsResponseRouterData, ResponseRouterData,
+    },
+    utils::{
+        self, AddressDetailsData, ApplePayDecrypt, BrowserInformationData, CardData,
+        MultipleCaptureSyncResponse, NetworkTokenData as _, PaymentsAuthorizeRequestData,
+        PaymentsSetupMandateRequestData, RouterData as _,
+    },
 };
 
 #[derive(Debug, Default, Eq, PartialEq, Serialize)]
 pub struct Payer {
     pub name: Option<Secret<String>>,
     pub email: Option<Email>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub phone: Option<Secret<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub address: Option<PayerAddress>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ip: Option<Secret<String, pii::IpAddress>>,
+    /// Device id generated by dLocal's fraud prevention script on the checkout page
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub device_id: Option<Secret<String>>,
     pub document: Secret<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub vpa: Option<Secret<String, UpiVpaMaskingStrategy>>,
+}
+
+#[derive(Debug, Default, Eq, PartialEq, Serialize)]
+pub struct PayerAddress {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub state: Option<Secret<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub city: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub zip_code: Option<Secret<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub street: Option<Secret<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub number: Option<Secret<String>>,
+}
+
+#[derive(Debug, Default, Eq, PartialEq, Serialize)]
+pub struct DlocalFraudData {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub device_id: Option<Secret<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub session_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ip: Option<Secret<String, pii::IpAddress>>,
 }
 
 #[derive(Debug, Default, Eq, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Card {
-    pub holder_name: Secret<String>,
-    pub number: cards::CardNumber,
-    pub cvv: Secret<String>,
-    pub expiration_month: Secret<String>,
-    pub expiration_year: Secret<String>,
-    pub capture: String,
+    /// Left out of hosted page payments, where the shopper enters it on dLocal's page
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub holder_name: Option<Secret<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub number: Option<cards::LastFourCardNumber>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cvv: Option<Secret<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expiration_month: Option<Secret<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expiration_year: Option<Secret<String>>,
+    /// Token of a card saved by a previous payment, sent instead of the card details
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub card_id: Option<String>,
+    /// Token of a card tokenized with dLocal, sent instead of the card details
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub token: Option<Secret<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub save: Option<bool>,
+    /// Network token sent in place of the card number, authenticated by its cryptogram
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub network_token: Option<NetworkTokenNumber>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cryptogram: Option<Secret<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tokenized: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stored_credential_type: Option<StoredCredentialType>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stored_credential_usage: Option<StoredCredentialUsage>,
+    /// Network transaction id of the customer initiated payment the card was stored with
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub network_tx_reference: Option<String>,
+    pub capture: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub installments_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub installments: Option<String>,
 }
 
+#[derive(Debug, Eq, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum StoredCredentialType {
+    UnscheduledCardOnFile,
+}
+
+#[derive(Debug, Eq, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum StoredCredentialUsage {
+    Used,
+}
+
+#[derive(Debug, Eq, PartialEq, Serialize)]
+#[serde(untagged)]
+pub enum ThreeDSecureReqData {
+    /// dLocal authenticates the payer
+    Dlocal(DlocalThreeDSecure),
+    /// The payer was already authenticated by the merchant's own 3DS server, dLocal passes the
+    /// result on to the issuer
+    External(DlocalExternalThreeDSecure),
+}
+
 #[derive(Debug, Default, Eq, PartialEq, Serialize)]
-pub struct ThreeDSecureReqData {
+pub struct DlocalThreeDSecure {
     pub force: bool,
+    /// Browser the payer authenticates on, lets the issuer authenticate without a challenge
+    pub browser_info: DlocalBrowserInfo,
 }
 
-#[derive(Debug, Serialize, Default, Deserialize, Clone, Eq, PartialEq)]
+#[derive(Debug, Eq, PartialEq, Serialize)]
+pub struct DlocalExternalThreeDSecure {
+    pub cavv: Secret<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub eci: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ds_transaction_id: Option<String>,
+    pub three_dsecure_version: String,
+}
+
+impl From<&AuthenticationData> for DlocalExternalThreeDSecure {
+    fn from(authentication_data: &AuthenticationData) -> Self {
+        Self {
+            cavv: Secret::new(authentication_data.cavv.clone()),
+            eci: authentication_data.eci.clone(),
+            ds_transaction_id: authentication_data.ds_trans_id.clone(),
+            three_dsecure_version: authentication_data.message_version.to_string(),
+        }
+    }
+}
+
+/// Device data of the payer's browser, 3DS 2.x can't collect it without the fields which aren't
+/// optional and falls back to a challenge
+#[derive(Debug, Default, Eq, PartialEq, Serialize)]
+pub struct DlocalBrowserInfo {
+    pub accept_header: String,
+    pub user_agent: String,
+    pub language: String,
+    pub screen_width: u32,
+    pub screen_height: u32,
+    pub color_depth: u8,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub java_enabled: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub javascript_enabled: Option<bool>,
+    /// Offset from UTC in minutes
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub time_zone: Option<i32>,
+}
+
+impl TryFrom<&BrowserInformation> for DlocalBrowserInfo {
+    type Error = error_stack::Report<errors::ConnectorError>;
+    fn try_from(browser_info: &BrowserInformation) -> Result<Self, Self::Error> {
+        Ok(Self {
+            accept_header: browser_info.get_accept_header()?,
+            user_agent: browser_info.get_user_agent()?,
+            language: browser_info.get_language()?,
+            screen_width: browser_info.get_screen_width()?,
+            screen_height: browser_info.get_screen_height()?,
+            color_depth: browser_info.get_color_depth()?,
+            java_enabled: browser_info.java_enabled,
+            javascript_enabled: browser_info.java_script_enabled,
+            time_zone: browser_info.time_zone,
+        })
+    }
+}
+
+/// 3DS is only forced for payments requiring it, the browser info is sent along with it
+fn get_three_dsecure(
+    auth_type: enums::AuthenticationType,
+    browser_info: Option<&BrowserInformation>,
+) -> Result<Option<ThreeDSecureReqData>, error_stack::Report<errors::ConnectorError>> {
+    match auth_type {
+        enums::AuthenticationType::ThreeDs => {
+            let browser_info =
+                browser_info.ok_or(errors::ConnectorError::MissingRequiredField {
+                    field_name: "browser_info",
+                })?;
+            Ok(Some(ThreeDSecureReqData::Dlocal(DlocalThreeDSecure {
+                force: true,
+                browser_info: DlocalBrowserInfo::try_from(browser_info)?,
+            })))
+        }
+        enums::AuthenticationType::NoThreeDs => Ok(None),
+    }
+}
+
+#[derive(Debug, Serialize, Default, Deserialize, Clone, Copy, Eq, PartialEq)]
 #[serde(rename_all = "UPPERCASE")]
 pub enum PaymentMethodId {
     #[default]
     Card,
+    Pix,
+    #[serde(rename = "BL")]
+    Boleto,
+    #[serde(rename = "OX")]
+    Oxxo,
+    #[serde(rename = "PC")]
+    Pse,
+    #[serde(rename = "UI")]
+    Upi,
 }
 
 #[derive(Debug, Serialize, Default, Deserialize, Clone, Eq, PartialEq)]
@@ -58,38 +274,102 @@ pub enum PaymentMethodFlow {
     ReDirect,
 }
 
+/// Payments the shopper completes away from the merchant's page, in a 3DS challenge or at their
+/// bank, are sent with the REDIRECT flow
+fn get_payment_method_flow(
+    payment_method_id: PaymentMethodId,
+    auth_type: enums::AuthenticationType,
+) -> PaymentMethodFlow {
+    match payment_method_id {
+        PaymentMethodId::Card => match auth_type {
+            enums::AuthenticationType::ThreeDs => PaymentMethodFlow::ReDirect,
+            enums::AuthenticationType::NoThreeDs => PaymentMethodFlow::Direct,
+        },
+        PaymentMethodId::Pix | PaymentMethodId::Pse => PaymentMethodFlow::ReDirect,
+        // Vouchers are paid with the barcode returned by the DIRECT flow, REDIRECT would only
+        // return dLocal's hosted page. UPI collect requests are approved in the shopper's app
+        PaymentMethodId::Boleto | PaymentMethodId::Oxxo | PaymentMethodId::Upi => {
+            PaymentMethodFlow::Direct
+        }
+    }
+}
+
 #[derive(Debug, Serialize)]
 pub struct DlocalRouterData<T> {
-    pub amount: i64,
+    pub amount: FloatMajorUnit,
     pub router_data: T,
 }
 
-impl<T> TryFrom<(&CurrencyUnit, enums::Currency, i64, T)> for DlocalRouterData<T> {
-    type Error = error_stack::Report<errors::ConnectorError>;
-
-    fn try_from(
-        (_currency_unit, _currency, amount, router_data): (&CurrencyUnit, enums::Currency, i64, T),
-    ) -> Result<Self, Self::Error> {
-        Ok(Self {
+impl<T> From<(FloatMajorUnit, T)> for DlocalRouterData<T> {
+    fn from((amount, router_data): (FloatMajorUnit, T)) -> Self {
+        Self {
             amount,
             router_data,
-        })
+        }
     }
 }
 
-#[derive(Default, Debug, Serialize, Eq, PartialEq)]
+#[derive(Default, Debug, Serialize, PartialEq)]
 pub struct DlocalPaymentsRequest {
-    pub amount: i64,
+    pub amount: FloatMajorUnit,
     pub currency: enums::Currency,
     pub country: String,
     pub payment_method_id: PaymentMethodId,
     pub payment_method_flow: PaymentMethodFlow,
     pub payer: Payer,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub card: Option<Card>,
     pub order_id: String,
     pub three_dsecure: Option<ThreeDSecureReqData>,
     pub callback_url: Option<String>,
+    /// Server to server notifications of asynchronous payments and late status changes
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub notification_url: Option<String>,
     pub description: Option<String>,
+    /// Device and session of the shopper, scored by dLocal's fraud prevention
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fraud: Option<DlocalFraudData>,
+    #[serde(
+        with = "common_utils::custom_serde::iso8601::option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub expiration_time: Option<time::PrimitiveDateTime>,
+}
+
+impl DlocalPaymentsRequest {
+    /// Request as sent to dLocal with the card details and the payer's document masked, safe to
+    /// be logged or attached to a support ticket
+    pub fn to_redacted_json(&self) -> CustomResult<serde_json::Value, errors::ConnectorError> {
+        masking::masked_serialize(self)
+            .change_context(errors::ConnectorError::RequestEncodingFailed)
+    }
+}
+
+/// Longest `order_id` and refund `id` accepted by dLocal
+const DLOCAL_MAX_REFERENCE_ID_LENGTH: usize = 128;
+
+/// dLocal only accepts letters, digits, `-` and `_` in the order and refund ids. Any other
+/// character is replaced by `_`, so a reference is always sent as the same id
+fn get_dlocal_reference_id(
+    reference_id: &str,
+    field_name: &'static str,
+) -> Result<String, error_stack::Report<errors::ConnectorError>> {
+    let received_length = reference_id.chars().count();
+    if received_length > DLOCAL_MAX_REFERENCE_ID_LENGTH {
+        Err(errors::ConnectorError::MaxFieldLengthViolated {
+            connector: "Dlocal",
+            field_name,
+            max_length: DLOCAL_MAX_REFERENCE_ID_LENGTH,
+            received_length,
+        })?
+    }
+    Ok(reference_id
+        .chars()
+        .map(|character| match character {
+            'a'..='z' | 'A'..='Z' | '0'..='9' | '-' | '_' => character,
+            _ => '_',
+        })
+        .collect())
 }
 
 impl TryFrom<&DlocalRouterData<&types::PaymentsAuthorizeRouterData>> for DlocalPaymentsRequest {
@@ -100,15 +380,152 @@ impl TryFrom<&DlocalRouterData<&types::PaymentsAuthorizeRouterData>> for DlocalP
         let email = item.router_data.request.email.clone();
         let address = item.router_data.get_billing_address()?;
         let country = address.get_country()?;
+        validate_supported_country(*country)?;
+        validate_currency_for_country(*country, item.router_data.request.currency)?;
         let name = get_payer_name(address);
+        let payer_address = get_payer_address(address);
+        let phone = get_payer_phone(
+            item.router_data
+                .get_optional_billing()
+                .and_then(|billing| billing.phone.as_ref()),
+        );
+        let order_id = get_dlocal_reference_id(
+            &item.router_data.connector_request_reference_id,
+            "connector_request_reference_id",
+        )?;
+        let payment_metadata = DlocalPaymentMetadata::try_from(&item.router_data.request.metadata)?;
+        let device_id = payment_metadata.device_id.clone();
+        let ip = item.router_data.request.get_ip_address_as_optional();
+        let fraud = get_fraud_data(
+            device_id.clone(),
+            item.router_data.request.session_token.clone(),
+            item.router_data.request.browser_info.as_ref(),
+        );
+        let connector_metadata =
+            DlocalConnectorMetadataObject::try_from(&item.router_data.connector_meta_data)?;
+        let document = payment_metadata.document;
+        if let Some(document) = &document {
+            validate_document(*country, document)?;
+        }
+        let document = document.or_else(|| {
+            connector_metadata
+                .document_mode
+                .get_placeholder_document(*country)
+        });
+        validate_minimum_amount(
+            *country,
+            item.router_data.request.currency,
+            item.router_data.request.minor_amount.get_amount_as_i64(),
+        )?;
         match item.router_data.request.payment_method_data {
             PaymentMethodData::Card(ref ccard) => {
-                let should_capture = matches!(
-                    item.router_data.request.capture_method,
-                    Some(enums::CaptureMethod::Automatic)
-                        | Some(enums::CaptureMethod::SequentialAutomatic)
-                );
+                let capture = should_capture(item.router_data.request.capture_method)?;
+                let card_payment_flow = connector_metadata.card_payment_flow.unwrap_or_default();
+                let (holder_name, number, cvv, expiration_month, expiration_year) =
+                    match card_payment_flow {
+                        PaymentMethodFlow::Direct => (
+                            Some(get_card_holder_name(
+                                ccard
+                                    .card_holder_name
+                                    .clone()
+                                    .or_else(|| item.router_data.get_optional_billing_full_name()),
+                                name.as_ref(),
+                            )?),
+                            Some(ccard.card_number.clone().into()),
+                            Some(ccard.card_cvc.clone()),
+                            Some(ccard.get_validated_expiry_month()?),
+                            Some(ccard.get_validated_expiry_year_4_digit()?),
+                        ),
+                        // The shopper enters the card details on dLocal's hosted page
+                        PaymentMethodFlow::ReDirect => (None, None, None, None, None),
+                    };
+                // Payers authenticated by the merchant's own 3DS server aren't redirected again
+                let external_three_dsecure = item
+                    .router_data
+                    .request
+                    .authentication_data
+                    .as_ref()
+                    .map(DlocalExternalThreeDSecure::from);
+                let payment_method_flow = match card_payment_flow {
+                    PaymentMethodFlow::Direct if external_three_dsecure.is_some() => {
+                        PaymentMethodFlow::Direct
+                    }
+                    PaymentMethodFlow::Direct => {
+                        get_payment_method_flow(PaymentMethodId::Card, item.router_data.auth_type)
+                    }
+                    PaymentMethodFlow::ReDirect => PaymentMethodFlow::ReDirect,
+                };
+                let is_redirect = payment_method_flow == PaymentMethodFlow::ReDirect;
                 let payment_request = Self {
+                    amount: item.amount,
+                    currency: item.router_data.request.currency,
+                    payment_method_id: PaymentMethodId::Card,
+                    payment_method_flow,
+                    country: country.to_string(),
+                    payer: Payer {
+                        name,
+                        email,
+                        phone,
+                        address: payer_address,
+                        ip,
+                        device_id,
+                        document: get_payer_document(document)?,
+                        vpa: None,
+                    },
+                    card: Some(Card {
+                        holder_name,
+                        number,
+                        cvv,
+                        expiration_month,
+                        expiration_year,
+                        card_id: None,
+                        save: should_save_card(item.router_data.request.setup_future_usage)
+                            .then_some(true),
+                        capture,
+                        installments_id: payment_metadata.installments_id.clone(),
+                        installments: get_installments(payment_metadata.installments)?,
+                        ..Default::default()
+                    }),
+                    order_id,
+                    three_dsecure: match external_three_dsecure {
+                        Some(external_three_dsecure) => {
+                            Some(ThreeDSecureReqData::External(external_three_dsecure))
+                        }
+                        None => get_three_dsecure(
+                            item.router_data.auth_type,
+                            item.router_data.request.browser_info.as_ref(),
+                        )?,
+                    },
+                    // The shopper returning from the 3DS challenge completes the authorization
+                    callback_url: Some(match item.router_data.auth_type {
+                        enums::AuthenticationType::ThreeDs => {
+                            item.router_data.request.get_complete_authorize_url()?
+                        }
+                        enums::AuthenticationType::NoThreeDs => {
+                            item.router_data.request.get_router_return_url()?
+                        }
+                    }),
+                    description: item.router_data.description.clone(),
+                    notification_url: item.router_data.request.webhook_url.clone(),
+                    fraud,
+                    expiration_time: get_expiration_time(
+                        payment_metadata.expiration_minutes,
+                        is_redirect,
+                    ),
+                };
+                Ok(payment_request)
+            }
+            PaymentMethodData::MandatePayment => {
+                let card_id = item.router_data.request.connector_mandate_id().ok_or(
+                    errors::ConnectorError::MissingRequiredField {
+                        field_name: "connector_mandate_id",
+                    },
+                )?;
+                let holder_name = get_card_holder_name(
+                    item.router_data.get_optional_billing_full_name(),
+                    name.as_ref(),
+                )?;
+                Ok(Self {
                     amount: item.amount,
                     currency: item.router_data.request.currency,
                     payment_method_id: PaymentMethodId::Card,
@@ -117,46 +534,719 @@ impl TryFrom<&DlocalRouterData<&types::PaymentsAuthorizeRouterData>> for DlocalP
                     payer: Payer {
                         name,
                         email,
-                        // [#589]: Allow securely collecting PII from customer in payments request
-                        document: get_doc_from_currency(country.to_string()),
+                        phone,
+                        address: payer_address,
+                        ip,
+                        device_id,
+                        document: get_payer_document(document)?,
+                        vpa: None,
+                    },
+                    card: Some(get_stored_card(
+                        holder_name,
+                        card_id,
+                        should_capture(item.router_data.request.capture_method)?,
+                    )),
+                    order_id,
+                    // Merchant initiated, the customer isn't present to complete a challenge
+                    three_dsecure: None,
+                    callback_url: Some(item.router_data.request.get_router_return_url()?),
+                    description: item.router_data.description.clone(),
+                    notification_url: item.router_data.request.webhook_url.clone(),
+                    fraud,
+                    expiration_time: get_expiration_time(
+                        payment_metadata.expiration_minutes,
+                        false,
+                    ),
+                })
+            }
+            // Card tokens come from dLocal, either issued for a saved card or created in the
+            // shopper's browser by Smart Fields, so the PAN and cvv never reach the server. 3DS is
+            // still forced on tokenized cards when the payment asks for it
+            PaymentMethodData::CardToken(ref card_token) => {
+                let token = match item.router_data.get_payment_method_token()? {
+                    PaymentMethodToken::Token(token) => token,
+                    PaymentMethodToken::ApplePayDecrypt(_)
+                    | PaymentMethodToken::GooglePayDecrypt(_)
+                    | PaymentMethodToken::PazeDecrypt(_) => {
+                        Err(errors::ConnectorError::NotImplemented(
+                            utils::get_unimplemented_payment_method_error_message("Dlocal"),
+                        ))?
+                    }
+                };
+                let holder_name = get_card_holder_name(
+                    card_token
+                        .card_holder_name
+                        .clone()
+                        .or(item.router_data.get_optional_billing_full_name()),
+                    name.as_ref(),
+                )?;
+                Ok(Self {
+                    amount: item.amount,
+                    currency: item.router_data.request.currency,
+                    payment_method_id: PaymentMethodId::Card,
+                    payment_method_flow: get_payment_method_flow(
+                        PaymentMethodId::Card,
+                        item.router_data.auth_type,
+                    ),
+                    country: country.to_string(),
+                    payer: Payer {
+                        // The payer's name is taken from the card when the billing address has none
+                        name: name.or_else(|| Some(holder_name.clone())),
+                        email,
+                        phone,
+                        address: payer_address,
+                        ip,
+                        device_id,
+                        document: get_payer_document(document)?,
+                        vpa: None,
                     },
                     card: Some(Card {
-                        holder_name: item
-                            .router_data
-                            .get_optional_billing_full_name()
-                            .unwrap_or(Secret::new("".to_string())),
-                        number: ccard.card_number.clone(),
-                        cvv: ccard.card_cvc.clone(),
-                        expiration_month: ccard.card_exp_month.clone(),
-                        expiration_year: ccard.card_exp_year.clone(),
-                        capture: should_capture.to_string(),
-                        installments_id: item
-                            .router_data
-                            .request
-                            .mandate_id
-                            .as_ref()
-                            .and_then(|ids| ids.mandate_id.clone()),
-                        // [#595[FEATURE] Pass Mandate history information in payment flows/request]
-                        installments: item
-                            .router_data
-                            .request
-                            .mandate_id
-                            .clone()
-                            .map(|_| "1".to_string()),
+                        installments_id: payment_metadata.installments_id.clone(),
+                        installments: get_installments(payment_metadata.installments)?,
+                        ..get_tokenized_card(
+                            holder_name,
+                            token,
+                            should_capture(item.router_data.request.capture_method)?,
+                        )
                     }),
-                    order_id: item.router_data.connector_request_reference_id.clone(),
-                    three_dsecure: match item.router_data.auth_type {
+                    order_id,
+                    three_dsecure: get_three_dsecure(
+                        item.router_data.auth_type,
+                        item.router_data.request.browser_info.as_ref(),
+                    )?,
+                    callback_url: Some(match item.router_data.auth_type {
                         enums::AuthenticationType::ThreeDs => {
-                            Some(ThreeDSecureReqData { force: true })
+                            item.router_data.request.get_complete_authorize_url()?
+                        }
+                        enums::AuthenticationType::NoThreeDs => {
+                            item.router_data.request.get_router_return_url()?
                         }
-                        enums::AuthenticationType::NoThreeDs => None,
+                    }),
+                    description: item.router_data.description.clone(),
+                    notification_url: item.router_data.request.webhook_url.clone(),
+                    fraud,
+                    expiration_time: get_expiration_time(
+                        payment_metadata.expiration_minutes,
+                        item.router_data.is_three_ds(),
+                    ),
+                })
+            }
+            PaymentMethodData::NetworkToken(ref token_data) => {
+                let holder_name = get_card_holder_name(
+                    item.router_data.get_optional_billing_full_name(),
+                    name.as_ref(),
+                )?;
+                Ok(Self {
+                    amount: item.amount,
+                    currency: item.router_data.request.currency,
+                    payment_method_id: PaymentMethodId::Card,
+                    payment_method_flow: get_payment_method_flow(
+                        PaymentMethodId::Card,
+                        item.router_data.auth_type,
+                    ),
+                    country: country.to_string(),
+                    payer: Payer {
+                        name,
+                        email,
+                        phone,
+                        address: payer_address,
+                        ip,
+                        device_id,
+                        document: get_payer_document(document)?,
+                        vpa: None,
+                    },
+                    card: Some(Card {
+                        holder_name: Some(holder_name),
+                        expiration_month: Some(token_data.get_network_token_expiry_month()),
+                        expiration_year: Some(token_data.get_network_token_expiry_year()),
+                        network_token: Some(token_data.get_network_token()),
+                        cryptogram: token_data.get_cryptogram(),
+                        tokenized: Some(true),
+                        capture: should_capture(item.router_data.request.capture_method)?,
+                        installments_id: payment_metadata.installments_id.clone(),
+                        installments: get_installments(payment_metadata.installments)?,
+                        ..Default::default()
+                    }),
+                    order_id,
+                    // The cryptogram already authenticates the token, 3DS only runs when forced
+                    three_dsecure: get_three_dsecure(
+                        item.router_data.auth_type,
+                        item.router_data.request.browser_info.as_ref(),
+                    )?,
+                    callback_url: Some(item.router_data.request.get_router_return_url()?),
+                    description: item.router_data.description.clone(),
+                    notification_url: item.router_data.request.webhook_url.clone(),
+                    fraud,
+                    expiration_time: get_expiration_time(
+                        payment_metadata.expiration_minutes,
+                        item.router_data.is_three_ds(),
+                    ),
+                })
+            }
+            PaymentMethodData::Wallet(ref wallet_data) => match wallet_data {
+                WalletData::ApplePay(_) | WalletData::GooglePay(_) => {
+                    let holder_name = get_card_holder_name(
+                        item.router_data.get_optional_billing_full_name(),
+                        name.as_ref(),
+                    )?;
+                    Ok(Self {
+                        amount: item.amount,
+                        currency: item.router_data.request.currency,
+                        payment_method_id: PaymentMethodId::Card,
+                        payment_method_flow: get_payment_method_flow(
+                            PaymentMethodId::Card,
+                            item.router_data.auth_type,
+                        ),
+                        country: country.to_string(),
+                        payer: Payer {
+                            name,
+                            email,
+                            phone,
+                            address: payer_address,
+                            ip,
+                            device_id,
+                            document: get_payer_document(document)?,
+                            vpa: None,
+                        },
+                        card: Some(Card {
+                            holder_name: Some(holder_name),
+                            capture: should_capture(item.router_data.request.capture_method)?,
+                            installments_id: payment_metadata.installments_id.clone(),
+                            installments: get_installments(payment_metadata.installments)?,
+                            ..get_wallet_card(item.router_data.payment_method_token.as_ref())?
+                        }),
+                        order_id,
+                        three_dsecure: get_three_dsecure(
+                            item.router_data.auth_type,
+                            item.router_data.request.browser_info.as_ref(),
+                        )?,
+                        callback_url: Some(item.router_data.request.get_router_return_url()?),
+                        description: item.router_data.description.clone(),
+                        notification_url: item.router_data.request.webhook_url.clone(),
+                        fraud,
+                        expiration_time: get_expiration_time(
+                            payment_metadata.expiration_minutes,
+                            item.router_data.is_three_ds(),
+                        ),
+                    })
+                }
+                WalletData::AliPayQr(_)
+                | WalletData::AliPayRedirect(_)
+                | WalletData::AliPayHkRedirect(_)
+                | WalletData::AmazonPayRedirect(_)
+                | WalletData::MomoRedirect(_)
+                | WalletData::KakaoPayRedirect(_)
+                | WalletData::GoPayRedirect(_)
+                | WalletData::GcashRedirect(_)
+                | WalletData::ApplePayRedirect(_)
+                | WalletData::ApplePayThirdPartySdk(_)
+                | WalletData::DanaRedirect {}
+                | WalletData::GooglePayRedirect(_)
+                | WalletData::GooglePayThirdPartySdk(_)
+                | WalletData::MbWayRedirect(_)
+                | WalletData::MobilePayRedirect(_)
+                | WalletData::PaypalRedirect(_)
+                | WalletData::PaypalSdk(_)
+                | WalletData::Paze(_)
+                | WalletData::SamsungPay(_)
+                | WalletData::TwintRedirect {}
+                | WalletData::VippsRedirect {}
+                | WalletData::TouchNGoRedirect(_)
+                | WalletData::WeChatPayRedirect(_)
+                | WalletData::WeChatPayQr(_)
+                | WalletData::CashappQr(_)
+                | WalletData::SwishQr(_)
+                | WalletData::Mifinity(_) => Err(errors::ConnectorError::NotImplemented(
+                    crate::utils::get_unimplemented_payment_method_error_message("Dlocal"),
+                ))?,
+            },
+            PaymentMethodData::CardDetailsForNetworkTransactionId(ref card_details) => {
+                let network_tx_reference = item
+                    .router_data
+                    .request
+                    .get_optional_network_transaction_id()
+                    .ok_or(errors::ConnectorError::MissingRequiredField {
+                        field_name: "network_transaction_id",
+                    })?;
+                let holder_name = get_card_holder_name(
+                    card_details
+                        .card_holder_name
+                        .clone()
+                        .or_else(|| item.router_data.get_optional_billing_full_name()),
+                    name.as_ref(),
+                )?;
+                Ok(Self {
+                    amount: item.amount,
+                    currency: item.router_data.request.currency,
+                    payment_method_id: PaymentMethodId::Card,
+                    payment_method_flow: PaymentMethodFlow::Direct,
+                    country: country.to_string(),
+                    payer: Payer {
+                        name: name.or_else(|| Some(holder_name.clone())),
+                        email,
+                        phone,
+                        address: payer_address,
+                        ip,
+                        device_id,
+                        document: get_payer_document(document)?,
+                        vpa: None,
                     },
+                    card: Some(Card {
+                        holder_name: Some(holder_name),
+                        number: Some(card_details.card_number.clone().into()),
+                        expiration_month: Some(card_details.get_validated_expiry_month()?),
+                        expiration_year: Some(card_details.get_validated_expiry_year_4_digit()?),
+                        stored_credential_type: Some(StoredCredentialType::UnscheduledCardOnFile),
+                        stored_credential_usage: Some(StoredCredentialUsage::Used),
+                        network_tx_reference: Some(network_tx_reference),
+                        capture: should_capture(item.router_data.request.capture_method)?,
+                        ..Default::default()
+                    }),
+                    order_id,
+                    // Merchant initiated payments are exempted from 3DS
+                    three_dsecure: None,
                     callback_url: Some(item.router_data.request.get_router_return_url()?),
                     description: item.router_data.description.clone(),
+                    notification_url: item.router_data.request.webhook_url.clone(),
+                    fraud,
+                    expiration_time: get_expiration_time(
+                        payment_metadata.expiration_minutes,
+                        false,
+                    ),
+                })
+            }
+            PaymentMethodData::BankTransfer(ref bank_transfer_data) => {
+                match bank_transfer_data.as_ref() {
+                    BankTransferData::Pix { cpf, cnpj, .. } => {
+                        validate_country_and_currency(
+                            *country,
+                            item.router_data.request.currency,
+                            enums::CountryAlpha2::BR,
+                            enums::Currency::BRL,
+                            "Pix",
+                        )?;
+                        for document in [cnpj, cpf].into_iter().flatten() {
+                            validate_document(*country, document)?;
+                        }
+                        Ok(Self {
+                            amount: item.amount,
+                            currency: item.router_data.request.currency,
+                            payment_method_id: PaymentMethodId::Pix,
+                            payment_method_flow: get_payment_method_flow(
+                                PaymentMethodId::Pix,
+                                item.router_data.auth_type,
+                            ),
+                            country: country.to_string(),
+                            payer: Payer {
+                                name,
+                                email,
+                                phone,
+                                address: payer_address,
+                                ip,
+                                device_id,
+                                document: get_payer_document(
+                                    cnpj.clone().or_else(|| cpf.clone()).or(document),
+                                )?,
+                                vpa: None,
+                            },
+                            card: None,
+                            order_id,
+                            three_dsecure: None,
+                            callback_url: Some(item.router_data.request.get_router_return_url()?),
+                            description: item.router_data.description.clone(),
+                            notification_url: item.router_data.request.webhook_url.clone(),
+                            fraud,
+                            expiration_time: get_expiration_time(
+                                payment_metadata.expiration_minutes,
+                                true,
+                            ),
+                        })
+                    }
+                    BankTransferData::Pse {} => {
+                        validate_country_and_currency(
+                            *country,
+                            item.router_data.request.currency,
+                            enums::CountryAlpha2::CO,
+                            enums::Currency::COP,
+                            "Pse",
+                        )?;
+                        Ok(Self {
+                            amount: item.amount,
+                            currency: item.router_data.request.currency,
+                            payment_method_id: PaymentMethodId::Pse,
+                            payment_method_flow: get_payment_method_flow(
+                                PaymentMethodId::Pse,
+                                item.router_data.auth_type,
+                            ),
+                            country: country.to_string(),
+                            payer: Payer {
+                                name,
+                                // PSE rejects payments without the payer's email
+                                email: Some(item.router_data.request.get_email()?),
+                                phone,
+                                address: payer_address,
+                                ip,
+                                device_id,
+                                document: get_payer_document(document)?,
+                                vpa: None,
+                            },
+                            card: None,
+                            order_id,
+                            three_dsecure: None,
+                            callback_url: Some(item.router_data.request.get_router_return_url()?),
+                            description: item.router_data.description.clone(),
+                            notification_url: item.router_data.request.webhook_url.clone(),
+                            fraud,
+                            expiration_time: get_expiration_time(
+                                payment_metadata.expiration_minutes,
+                                true,
+                            ),
+                        })
+                    }
+                    BankTransferData::AchBankTransfer {}
+                    | BankTransferData::SepaBankTransfer {}
+                    | BankTransferData::BacsBankTransfer {}
+                    | BankTransferData::MultibancoBankTransfer {}
+                    | BankTransferData::PermataBankTransfer {}
+                    | BankTransferData::BcaBankTransfer {}
+                    | BankTransferData::BniVaBankTransfer {}
+                    | BankTransferData::BriVaBankTransfer {}
+                    | BankTransferData::CimbVaBankTransfer {}
+                    | BankTransferData::DanamonVaBankTransfer {}
+                    | BankTransferData::MandiriVaBankTransfer {}
+                    | BankTransferData::LocalBankTransfer { .. } => {
+                        Err(errors::ConnectorError::NotImplemented(
+                            crate::utils::get_unimplemented_payment_method_error_message("Dlocal"),
+                        ))?
+                    }
+                }
+            }
+            PaymentMethodData::Voucher(ref voucher_data) => {
+                let (payment_method_id, payer_name, document) = match voucher_data {
+                    VoucherData::Boleto(boleto_data) => {
+                        validate_country_and_currency(
+                            *country,
+                            item.router_data.request.currency,
+                            enums::CountryAlpha2::BR,
+                            enums::Currency::BRL,
+                            "Boleto",
+                        )?;
+                        let document = boleto_data
+                            .social_security_number
+                            .clone()
+                            .or(document)
+                            .ok_or(errors::ConnectorError::MissingRequiredField {
+                                field_name:
+                                    "payment_method_data.voucher.boleto.social_security_number",
+                            })?;
+                        validate_document(*country, &document)?;
+                        let name = name.ok_or(errors::ConnectorError::MissingRequiredField {
+                            field_name: "billing.address.first_name",
+                        })?;
+                        (PaymentMethodId::Boleto, Some(name), document)
+                    }
+                    VoucherData::Oxxo => {
+                        validate_country_and_currency(
+                            *country,
+                            item.router_data.request.currency,
+                            enums::CountryAlpha2::MX,
+                            enums::Currency::MXN,
+                            "Oxxo",
+                        )?;
+                        (PaymentMethodId::Oxxo, name, get_payer_document(document)?)
+                    }
+                    VoucherData::Efecty
+                    | VoucherData::PagoEfectivo
+                    | VoucherData::RedCompra
+                    | VoucherData::RedPagos
+                    | VoucherData::Alfamart(_)
+                    | VoucherData::Indomaret(_)
+                    | VoucherData::SevenEleven(_)
+                    | VoucherData::Lawson(_)
+                    | VoucherData::MiniStop(_)
+                    | VoucherData::FamilyMart(_)
+                    | VoucherData::Seicomart(_)
+                    | VoucherData::PayEasy(_) => Err(errors::ConnectorError::NotImplemented(
+                        crate::utils::get_unimplemented_payment_method_error_message("Dlocal"),
+                    ))?,
                 };
-                Ok(payment_request)
+                Ok(Self {
+                    amount: item.amount,
+                    currency: item.router_data.request.currency,
+                    payment_method_id,
+                    payment_method_flow: get_payment_method_flow(
+                        payment_method_id,
+                        item.router_data.auth_type,
+                    ),
+                    country: country.to_string(),
+                    payer: Payer {
+                        name: payer_name,
+                        email,
+                        phone,
+                        address: payer_address,
+                        ip,
+                        device_id,
+                        document,
+                        vpa: None,
+                    },
+                    card: None,
+                    order_id,
+                    three_dsecure: None,
+                    callback_url: Some(item.router_data.request.get_router_return_url()?),
+                    description: item.router_data.description.clone(),
+                    notification_url: item.router_data.request.webhook_url.clone(),
+                    fraud,
+                    expiration_time: get_expiration_time(
+                        payment_metadata.expiration_minutes,
+                        false,
+                    ),
+                })
             }
+            PaymentMethodData::Upi(ref upi_data) => match upi_data {
+                UpiData::UpiCollect(upi_collect_data) => {
+                    validate_country_and_currency(
+                        *country,
+                        item.router_data.request.currency,
+                        enums::CountryAlpha2::IN,
+                        enums::Currency::INR,
+                        "Upi",
+                    )?;
+                    Ok(Self {
+                        amount: item.amount,
+                        currency: item.router_data.request.currency,
+                        payment_method_id: PaymentMethodId::Upi,
+                        payment_method_flow: get_payment_method_flow(
+                            PaymentMethodId::Upi,
+                            item.router_data.auth_type,
+                        ),
+                        country: country.to_string(),
+                        payer: Payer {
+                            name,
+                            email,
+                            phone,
+                            address: payer_address,
+                            ip,
+                            device_id,
+                            // The PAN of the payer
+                            document: get_payer_document(document)?,
+                            vpa: Some(upi_collect_data.vpa_id.clone().ok_or(
+                                errors::ConnectorError::MissingRequiredField {
+                                    field_name: "payment_method_data.upi.upi_collect.vpa_id",
+                                },
+                            )?),
+                        },
+                        card: None,
+                        order_id,
+                        three_dsecure: None,
+                        callback_url: Some(item.router_data.request.get_router_return_url()?),
+                        description: item.router_data.description.clone(),
+                        notification_url: item.router_data.request.webhook_url.clone(),
+                        fraud,
+                        expiration_time: get_expiration_time(
+                            payment_metadata.expiration_minutes,
+                            false,
+                        ),
+                    })
+                }
+                UpiData::UpiIntent(_) => Err(errors::ConnectorError::NotImplemented(
+                    crate::utils::get_unimplemented_payment_method_error_message("Dlocal"),
+                ))?,
+            },
             PaymentMethodData::CardRedirect(_)
+            | PaymentMethodData::PayLater(_)
+            | PaymentMethodData::BankRedirect(_)
+            | PaymentMethodData::BankDebit(_)
+            | PaymentMethodData::Crypto(_)
+            | PaymentMethodData::Reward
+            | PaymentMethodData::RealTimePayment(_)
+            | PaymentMethodData::MobilePayment(_)
+            | PaymentMethodData::GiftCard(_)
+            | PaymentMethodData::OpenBanking(_) => Err(errors::ConnectorError::NotImplemented(
+                crate::utils::get_unimplemented_payment_method_error_message("Dlocal"),
+            ))?,
+        }
+    }
+}
+
+/// Apple Pay and Google Pay payments are sent as network tokens, with the DPAN and cryptogram
+/// of the wallet token decrypted by the router
+fn get_wallet_card(
+    payment_method_token: Option<&PaymentMethodToken>,
+) -> Result<Card, error_stack::Report<errors::ConnectorError>> {
+    match payment_method_token {
+        Some(PaymentMethodToken::ApplePayDecrypt(decrypt_data)) => Ok(Card {
+            expiration_month: Some(decrypt_data.get_expiry_month()?),
+            expiration_year: Some(decrypt_data.get_four_digit_expiry_year()?),
+            network_token: Some(get_wallet_network_token(
+                decrypt_data.application_primary_account_number.peek(),
+            )?),
+            cryptogram: Some(decrypt_data.payment_data.online_payment_cryptogram.clone()),
+            tokenized: Some(true),
+            ..Default::default()
+        }),
+        Some(PaymentMethodToken::GooglePayDecrypt(decrypt_data)) => {
+            let details = &decrypt_data.payment_method_details;
+            let cryptogram =
+                details
+                    .cryptogram
+                    .clone()
+                    .ok_or(errors::ConnectorError::MissingRequiredField {
+                        field_name: "google_pay.cryptogram",
+                    })?;
+            Ok(Card {
+                expiration_month: Some(Secret::new(details.expiration_month.two_digits())),
+                expiration_year: Some(Secret::new(details.expiration_year.four_digits())),
+                network_token: Some(get_wallet_network_token(details.pan.peek())?),
+                cryptogram: Some(cryptogram),
+                tokenized: Some(true),
+                ..Default::default()
+            })
+        }
+        Some(PaymentMethodToken::Token(_)) | Some(PaymentMethodToken::PazeDecrypt(_)) | None => {
+            Err(errors::ConnectorError::MissingRequiredField {
+                field_name: "decrypted_wallet_token",
+            })?
+        }
+    }
+}
+
+fn get_wallet_network_token(
+    dpan: &str,
+) -> Result<NetworkTokenNumber, error_stack::Report<errors::ConnectorError>> {
+    NetworkTokenNumber::from_str(dpan).change_context(errors::ConnectorError::InvalidDataFormat {
+        field_name: "payment_method_token.pan",
+    })
+}
+
+/// dLocal captures a card payment along with the authorization when `capture` is true, manual
+/// captures of one or more parts of the authorization are made with the capture request
+fn should_capture(
+    capture_method: Option<enums::CaptureMethod>,
+) -> Result<bool, error_stack::Report<errors::ConnectorError>> {
+    match capture_method {
+        Some(enums::CaptureMethod::Automatic)
+        | Some(enums::CaptureMethod::SequentialAutomatic)
+        | None => Ok(true),
+        Some(enums::CaptureMethod::Manual) | Some(enums::CaptureMethod::ManualMultiple) => {
+            Ok(false)
+        }
+        Some(enums::CaptureMethod::Scheduled) => {
+            Err(errors::ConnectorError::CaptureMethodNotSupported.into())
+        }
+    }
+}
+
+/// Card saved by the customer initiated payment, charged by its `card_id` as a merchant
+/// initiated payment so no cvv is needed
+fn get_stored_card(holder_name: Secret<String>, card_id: String, capture: bool) -> Card {
+    Card {
+        holder_name: Some(holder_name),
+        card_id: Some(card_id),
+        stored_credential_type: Some(StoredCredentialType::UnscheduledCardOnFile),
+        stored_credential_usage: Some(StoredCredentialUsage::Used),
+        capture,
+        ..Default::default()
+    }
+}
+
+/// Card of a repeat purchase, charged with the token dLocal issued for it so neither the card
+/// number nor the cvv is sent
+fn get_tokenized_card(holder_name: Secret<String>, token: Secret<String>, capture: bool) -> Card {
+    Card {
+        holder_name: Some(holder_name),
+        token: Some(token),
+        capture,
+        ..Default::default()
+    }
+}
+
+impl TryFrom<&types::SetupMandateRouterData> for DlocalPaymentsRequest {
+    type Error = error_stack::Report<errors::ConnectorError>;
+    fn try_from(item: &types::SetupMandateRouterData) -> Result<Self, Self::Error> {
+        match item.request.payment_method_data {
+            PaymentMethodData::Card(ref ccard) => {
+                let address = item.get_billing_address()?;
+                let country = address.get_country()?;
+                validate_supported_country(*country)?;
+                validate_currency_for_country(*country, item.request.currency)?;
+                let payment_metadata = DlocalPaymentMetadata::try_from(
+                    &item.request.metadata.clone().map(ExposeInterface::expose),
+                )?;
+                let connector_metadata =
+                    DlocalConnectorMetadataObject::try_from(&item.connector_meta_data)?;
+                let fraud = get_fraud_data(
+                    payment_metadata.device_id.clone(),
+                    None,
+                    item.request.browser_info.as_ref(),
+                );
+                let document = payment_metadata.document;
+                if let Some(document) = &document {
+                    validate_document(*country, document)?;
+                }
+                let document = document.or_else(|| {
+                    connector_metadata
+                        .document_mode
+                        .get_placeholder_document(*country)
+                });
+                Ok(Self {
+                    // A zero amount payment only verifies the card and saves it for later use
+                    amount: FloatMajorUnit::zero(),
+                    currency: item.request.currency,
+                    payment_method_id: PaymentMethodId::Card,
+                    payment_method_flow: get_payment_method_flow(
+                        PaymentMethodId::Card,
+                        item.auth_type,
+                    ),
+                    country: country.to_string(),
+                    payer: Payer {
+                        name: get_payer_name(address),
+                        email: item.request.email.clone(),
+                        phone: get_payer_phone(
+                            item.get_optional_billing()
+                                .and_then(|billing| billing.phone.as_ref()),
+                        ),
+                        address: get_payer_address(address),
+                        ip: item.request.get_ip_address_as_optional(),
+                        device_id: payment_metadata.device_id.clone(),
+                        document: get_payer_document(document)?,
+                        vpa: None,
+                    },
+                    card: Some(Card {
+                        holder_name: Some(get_card_holder_name(
+                            ccard
+                                .card_holder_name
+                                .clone()
+                                .or_else(|| item.get_optional_billing_full_name()),
+                            get_payer_name(address).as_ref(),
+                        )?),
+                        number: Some(ccard.card_number.clone().into()),
+                        cvv: Some(ccard.card_cvc.clone()),
+                        expiration_month: Some(ccard.get_validated_expiry_month()?),
+                        expiration_year: Some(ccard.get_validated_expiry_year_4_digit()?),
+                        save: Some(true),
+                        capture: false,
+                        ..Default::default()
+                    }),
+                    order_id: get_dlocal_reference_id(
+                        &item.connector_request_reference_id,
+                        "connector_request_reference_id",
+                    )?,
+                    three_dsecure: get_three_dsecure(
+                        item.auth_type,
+                        item.request.browser_info.as_ref(),
+                    )?,
+                    callback_url: item.request.router_return_url.clone(),
+                    description: item.description.clone(),
+                    notification_url: item.request.webhook_url.clone(),
+                    fraud,
+                    expiration_time: get_expiration_time(
+                        payment_metadata.expiration_minutes,
+                        item.is_three_ds(),
+                    ),
+                })
+            }
+            PaymentMethodData::CardDetailsForNetworkTransactionId(_)
+            | PaymentMethodData::CardRedirect(_)
             | PaymentMethodData::Wallet(_)
             | PaymentMethodData::PayLater(_)
             | PaymentMethodData::BankRedirect(_)
@@ -166,102 +1256,733 @@ impl TryFrom<&DlocalRouterData<&types::PaymentsAuthorizeRouterData>> for DlocalP
             | PaymentMethodData::MandatePayment
             | PaymentMethodData::Reward
             | PaymentMethodData::RealTimePayment(_)
-            | PaymentMethodData::MobilePayment(_)
             | PaymentMethodData::Upi(_)
             | PaymentMethodData::Voucher(_)
             | PaymentMethodData::GiftCard(_)
-            | PaymentMethodData::OpenBanking(_)
             | PaymentMethodData::CardToken(_)
+            | PaymentMethodData::OpenBanking(_)
             | PaymentMethodData::NetworkToken(_)
-            | PaymentMethodData::CardDetailsForNetworkTransactionId(_) => {
-                Err(errors::ConnectorError::NotImplemented(
-                    crate::utils::get_unimplemented_payment_method_error_message("Dlocal"),
-                ))?
-            }
+            | PaymentMethodData::MobilePayment(_) => Err(errors::ConnectorError::NotImplemented(
+                utils::get_unimplemented_payment_method_error_message("Dlocal"),
+            ))?,
         }
     }
 }
 
-fn get_payer_name(
-    address: &hyperswitch_domain_models::address::AddressDetails,
-) -> Option<Secret<String>> {
-    let first_name = address
-        .first_name
-        .clone()
-        .map_or("".to_string(), |first_name| first_name.peek().to_string());
-    let last_name = address
-        .last_name
-        .clone()
-        .map_or("".to_string(), |last_name| last_name.peek().to_string());
-    let name: String = format!("{first_name} {last_name}").trim().to_string();
-    if !name.is_empty() {
-        Some(Secret::new(name))
-    } else {
-        None
+/// Merchant account level configuration, card payments use the direct flow unless
+/// `"card_payment_flow": "REDIRECT"` is set in the connector metadata and payers without a
+/// document are declined unless `"document_mode": "SANDBOX"` is set
+#[derive(Debug, Default, Deserialize)]
+pub struct DlocalConnectorMetadataObject {
+    pub card_payment_flow: Option<PaymentMethodFlow>,
+    #[serde(default)]
+    pub document_mode: DlocalDocumentMode,
+}
+
+/// dLocal's sandbox accepts placeholder documents, in production the payer's own document has to
+/// be sent as the placeholders would reach real acquirers
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum DlocalDocumentMode {
+    Sandbox,
+    #[default]
+    Production,
+}
+
+impl DlocalDocumentMode {
+    fn get_placeholder_document(self, country: enums::CountryAlpha2) -> Option<Secret<String>> {
+        match self {
+            Self::Sandbox => Some(get_sandbox_document(country)),
+            Self::Production => None,
+        }
     }
 }
 
-pub struct DlocalPaymentsSyncRequest {
-    pub authz_id: String,
+/// Documents accepted by dLocal's sandbox, these are not checked against the country's format
+fn get_sandbox_document(country: enums::CountryAlpha2) -> Secret<String> {
+    let document = match country {
+        enums::CountryAlpha2::BR => "91483309223",
+        enums::CountryAlpha2::ZA => "2001014800086",
+        enums::CountryAlpha2::BD
+        | enums::CountryAlpha2::GT
+        | enums::CountryAlpha2::HN
+        | enums::CountryAlpha2::PK
+        | enums::CountryAlpha2::SN
+        | enums::CountryAlpha2::TH => "1234567890001",
+        enums::CountryAlpha2::CR | enums::CountryAlpha2::SV | enums::CountryAlpha2::VN => {
+            "123456789"
+        }
+        enums::CountryAlpha2::DO | enums::CountryAlpha2::NG => "12345678901",
+        enums::CountryAlpha2::EG => "12345678901112",
+        enums::CountryAlpha2::GH
+        | enums::CountryAlpha2::ID
+        | enums::CountryAlpha2::RW
+        | enums::CountryAlpha2::UG => "1234567890111123",
+        enums::CountryAlpha2::IN => "NHSTP6374G",
+        enums::CountryAlpha2::CI => "CA124356789",
+        enums::CountryAlpha2::JP | enums::CountryAlpha2::MY | enums::CountryAlpha2::PH => {
+            "123456789012"
+        }
+        enums::CountryAlpha2::NI => "1234567890111A",
+        enums::CountryAlpha2::TZ => "12345678912345678900",
+        _ => "12345678",
+    };
+    Secret::new(document.to_string())
 }
 
-impl TryFrom<&types::PaymentsSyncRouterData> for DlocalPaymentsSyncRequest {
+impl TryFrom<&Option<pii::SecretSerdeValue>> for DlocalConnectorMetadataObject {
     type Error = error_stack::Report<errors::ConnectorError>;
-    fn try_from(item: &types::PaymentsSyncRouterData) -> Result<Self, Self::Error> {
-        Ok(Self {
-            authz_id: (item
-                .request
-                .connector_transaction_id
-                .get_connector_transaction_id()
-                .change_context(errors::ConnectorError::MissingConnectorTransactionID)?),
-        })
+    fn try_from(meta_data: &Option<pii::SecretSerdeValue>) -> Result<Self, Self::Error> {
+        meta_data
+            .clone()
+            .map(|meta_data| {
+                utils::to_connector_meta_from_secret::<Self>(Some(meta_data)).change_context(
+                    errors::ConnectorError::InvalidConnectorConfig { config: "metadata" },
+                )
+            })
+            .transpose()
+            .map(Option::unwrap_or_default)
     }
 }
 
-pub struct DlocalPaymentsCancelRequest {
-    pub cancel_id: String,
+/// Payment details which are not part of the payment request, passed by the merchant in the
+/// payment metadata, e.g. `"metadata": { "document": "53033315550", "installments": 12 }`
+#[derive(Debug, Default, Deserialize)]
+pub struct DlocalPaymentMetadata {
+    pub document: Option<Secret<String>>,
+    /// Number of installments the card payment is split into
+    pub installments: Option<u8>,
+    /// Id of an installments plan created beforehand through dLocal's installments plan API
+    pub installments_id: Option<String>,
+    /// Minutes the shopper has to complete the payment before dLocal expires it
+    pub expiration_minutes: Option<u16>,
+    /// Device id generated by dLocal's fraud prevention script on the checkout page
+    pub device_id: Option<Secret<String>>,
+}
+
+/// The fraud block is left out of the request when none of its fields are known
+fn get_fraud_data(
+    device_id: Option<Secret<String>>,
+    session_id: Option<String>,
+    browser_info: Option<&BrowserInformation>,
+) -> Option<DlocalFraudData> {
+    let ip = browser_info
+        .and_then(|browser_info| browser_info.ip_address)
+        .map(|ip_address| Secret::new(ip_address.to_string()));
+    (device_id.is_some() || session_id.is_some() || ip.is_some()).then_some(DlocalFraudData {
+        device_id,
+        session_id,
+        ip,
+    })
+}
+
+/// Minutes given to the shopper to complete a redirect (3DS challenge, Pix, PSE) payment when
+/// the merchant didn't pass `expiration_minutes`
+const DEFAULT_REDIRECT_EXPIRATION_MINUTES: u16 = 60;
+
+/// Abandoned redirect payments expire instead of staying pending, which lets them be reconciled
+/// as failed. Direct payments only expire when the merchant asks for it
+fn get_expiration_time(
+    expiration_minutes: Option<u16>,
+    is_redirect: bool,
+) -> Option<time::PrimitiveDateTime> {
+    expiration_minutes
+        .or(is_redirect.then_some(DEFAULT_REDIRECT_EXPIRATION_MINUTES))
+        .map(|minutes| date_time::now().saturating_add(time::Duration::minutes(minutes.into())))
 }
 
-impl TryFrom<&types::PaymentsCancelRouterData> for DlocalPaymentsCancelRequest {
+impl TryFrom<&Option<serde_json::Value>> for DlocalPaymentMetadata {
     type Error = error_stack::Report<errors::ConnectorError>;
-    fn try_from(item: &types::PaymentsCancelRouterData) -> Result<Self, Self::Error> {
-        Ok(Self {
-            cancel_id: item.request.connector_transaction_id.clone(),
+    fn try_from(metadata: &Option<serde_json::Value>) -> Result<Self, Self::Error> {
+        metadata
+            .clone()
+            .map(|metadata| utils::to_connector_meta::<Self>(Some(metadata)))
+            .transpose()
+            .map(Option::unwrap_or_default)
+    }
+}
+
+/// dLocal accepts card payments split into at most 36 installments
+fn get_installments(
+    installments: Option<u8>,
+) -> Result<Option<String>, error_stack::Report<errors::ConnectorError>> {
+    installments
+        .map(|installments| {
+            if (1..=36).contains(&installments) {
+                Ok(installments.to_string())
+            } else {
+                Err(errors::ConnectorError::InvalidDataFormat {
+                    field_name: "metadata.installments",
+                })
+                .attach_printable(format!(
+                    "installments must be between 1 and 36, got {installments}"
+                ))
+            }
         })
+        .transpose()
+}
+
+/// Countries dLocal accepts payments from, others are declined by dLocal without a reason
+fn is_supported_country(country: enums::CountryAlpha2) -> bool {
+    matches!(
+        country,
+        // Latin America
+        enums::CountryAlpha2::AR
+            | enums::CountryAlpha2::BO
+            | enums::CountryAlpha2::BR
+            | enums::CountryAlpha2::CL
+            | enums::CountryAlpha2::CO
+            | enums::CountryAlpha2::CR
+            | enums::CountryAlpha2::DO
+            | enums::CountryAlpha2::EC
+            | enums::CountryAlpha2::SV
+            | enums::CountryAlpha2::GT
+            | enums::CountryAlpha2::HN
+            | enums::CountryAlpha2::MX
+            | enums::CountryAlpha2::NI
+            | enums::CountryAlpha2::PA
+            | enums::CountryAlpha2::PY
+            | enums::CountryAlpha2::PE
+            | enums::CountryAlpha2::UY
+            // Africa and the Middle East
+            | enums::CountryAlpha2::CM
+            | enums::CountryAlpha2::EG
+            | enums::CountryAlpha2::GH
+            | enums::CountryAlpha2::CI
+            | enums::CountryAlpha2::KE
+            | enums::CountryAlpha2::MA
+            | enums::CountryAlpha2::NG
+            | enums::CountryAlpha2::RW
+            | enums::CountryAlpha2::SA
+            | enums::CountryAlpha2::SN
+            | enums::CountryAlpha2::ZA
+            | enums::CountryAlpha2::TZ
+            | enums::CountryAlpha2::TR
+            | enums::CountryAlpha2::UG
+            | enums::CountryAlpha2::AE
+            | enums::CountryAlpha2::ZM
+            // Asia
+            | enums::CountryAlpha2::BD
+            | enums::CountryAlpha2::CN
+            | enums::CountryAlpha2::IN
+            | enums::CountryAlpha2::ID
+            | enums::CountryAlpha2::JP
+            | enums::CountryAlpha2::MY
+            | enums::CountryAlpha2::PK
+            | enums::CountryAlpha2::PH
+            | enums::CountryAlpha2::KR
+            | enums::CountryAlpha2::TH
+            | enums::CountryAlpha2::VN
+    )
+}
+
+fn validate_supported_country(
+    country: enums::CountryAlpha2,
+) -> Result<(), error_stack::Report<errors::ConnectorError>> {
+    if !is_supported_country(country) {
+        Err(errors::ConnectorError::NotSupported {
+            message: format!("billing country {country}"),
+            connector: "Dlocal",
+        })?
     }
+    Ok(())
 }
 
-#[derive(Default, Debug, Serialize, Eq, PartialEq)]
-pub struct DlocalPaymentsCaptureRequest {
-    pub authorization_id: String,
-    pub amount: i64,
-    pub currency: String,
-    pub order_id: String,
+/// Local currency dLocal processes payments of the country in
+fn get_local_currency(country: enums::CountryAlpha2) -> Option<enums::Currency> {
+    match country {
+        enums::CountryAlpha2::AR => Some(enums::Currency::ARS),
+        enums::CountryAlpha2::BO => Some(enums::Currency::BOB),
+        enums::CountryAlpha2::BR => Some(enums::Currency::BRL),
+        enums::CountryAlpha2::CL => Some(enums::Currency::CLP),
+        enums::CountryAlpha2::CO => Some(enums::Currency::COP),
+        enums::CountryAlpha2::CR => Some(enums::Currency::CRC),
+        enums::CountryAlpha2::DO => Some(enums::Currency::DOP),
+        enums::CountryAlpha2::EC => Some(enums::Currency::USD),
+        enums::CountryAlpha2::SV => Some(enums::Currency::USD),
+        enums::CountryAlpha2::GT => Some(enums::Currency::GTQ),
+        enums::CountryAlpha2::HN => Some(enums::Currency::HNL),
+        enums::CountryAlpha2::MX => Some(enums::Currency::MXN),
+        enums::CountryAlpha2::NI => Some(enums::Currency::NIO),
+        enums::CountryAlpha2::PA => Some(enums::Currency::USD),
+        enums::CountryAlpha2::PY => Some(enums::Currency::PYG),
+        enums::CountryAlpha2::PE => Some(enums::Currency::PEN),
+        enums::CountryAlpha2::UY => Some(enums::Currency::UYU),
+        enums::CountryAlpha2::CM => Some(enums::Currency::XAF),
+        enums::CountryAlpha2::EG => Some(enums::Currency::EGP),
+        enums::CountryAlpha2::GH => Some(enums::Currency::GHS),
+        enums::CountryAlpha2::CI => Some(enums::Currency::XOF),
+        enums::CountryAlpha2::KE => Some(enums::Currency::KES),
+        enums::CountryAlpha2::MA => Some(enums::Currency::MAD),
+        enums::CountryAlpha2::NG => Some(enums::Currency::NGN),
+        enums::CountryAlpha2::RW => Some(enums::Currency::RWF),
+        enums::CountryAlpha2::SA => Some(enums::Currency::SAR),
+        enums::CountryAlpha2::SN => Some(enums::Currency::XOF),
+        enums::CountryAlpha2::ZA => Some(enums::Currency::ZAR),
+        enums::CountryAlpha2::TZ => Some(enums::Currency::TZS),
+        enums::CountryAlpha2::TR => Some(enums::Currency::TRY),
+        enums::CountryAlpha2::UG => Some(enums::Currency::UGX),
+        enums::CountryAlpha2::AE => Some(enums::Currency::AED),
+        enums::CountryAlpha2::ZM => Some(enums::Currency::ZMW),
+        enums::CountryAlpha2::BD => Some(enums::Currency::BDT),
+        enums::CountryAlpha2::CN => Some(enums::Currency::CNY),
+        enums::CountryAlpha2::IN => Some(enums::Currency::INR),
+        enums::CountryAlpha2::ID => Some(enums::Currency::IDR),
+        enums::CountryAlpha2::JP => Some(enums::Currency::JPY),
+        enums::CountryAlpha2::MY => Some(enums::Currency::MYR),
+        enums::CountryAlpha2::PK => Some(enums::Currency::PKR),
+        enums::CountryAlpha2::PH => Some(enums::Currency::PHP),
+        enums::CountryAlpha2::KR => Some(enums::Currency::KRW),
+        enums::CountryAlpha2::TH => Some(enums::Currency::THB),
+        enums::CountryAlpha2::VN => Some(enums::Currency::VND),
+        _ => None,
+    }
 }
 
-impl TryFrom<&types::PaymentsCaptureRouterData> for DlocalPaymentsCaptureRequest {
-    type Error = error_stack::Report<errors::ConnectorError>;
-    fn try_from(item: &types::PaymentsCaptureRouterData) -> Result<Self, Self::Error> {
-        Ok(Self {
-            authorization_id: item.request.connector_transaction_id.clone(),
-            amount: item.request.amount_to_capture,
-            currency: item.request.currency.to_string(),
-            order_id: item.connector_request_reference_id.clone(),
-        })
+/// dLocal accepts payments in the local currency of the billing country, or in USD which it
+/// converts to the local currency. Countries without a known local currency are left to dLocal
+fn validate_currency_for_country(
+    country: enums::CountryAlpha2,
+    currency: enums::Currency,
+) -> Result<(), error_stack::Report<errors::ConnectorError>> {
+    match get_local_currency(country) {
+        Some(local_currency) if currency != local_currency && currency != enums::Currency::USD => {
+            Err(errors::ConnectorError::NotSupported {
+                message: format!("{currency} payments for billing country {country}"),
+                connector: "Dlocal",
+            })?
+        }
+        _ => Ok(()),
     }
 }
-// Auth Struct
-pub struct DlocalAuthType {
-    pub(super) x_login: Secret<String>,
-    pub(super) x_trans_key: Secret<String>,
-    pub(super) secret: Secret<String>,
+
+fn validate_country_and_currency(
+    country: enums::CountryAlpha2,
+    currency: enums::Currency,
+    expected_country: enums::CountryAlpha2,
+    expected_currency: enums::Currency,
+    payment_method: &'static str,
+) -> Result<(), error_stack::Report<errors::ConnectorError>> {
+    if country != expected_country {
+        Err(errors::ConnectorError::NotSupported {
+            message: format!("{payment_method} for billing country {country}"),
+            connector: "Dlocal",
+        })?
+    }
+    if currency != expected_currency {
+        Err(errors::ConnectorError::CurrencyNotSupported {
+            message: format!("{currency} for {payment_method}"),
+            connector: "Dlocal",
+        })?
+    }
+    Ok(())
 }
 
-impl TryFrom<&ConnectorAuthType> for DlocalAuthType {
-    type Error = error_stack::Report<errors::ConnectorError>;
-    fn try_from(auth_type: &ConnectorAuthType) -> Result<Self, Self::Error> {
-        if let ConnectorAuthType::SignatureKey {
-            api_key,
+/// Smallest amount, in the minor unit of the currency, dLocal accepts for a payment in the local
+/// currency of the country
+fn get_minimum_amount(country: enums::CountryAlpha2, currency: enums::Currency) -> Option<i64> {
+    match (country, currency) {
+        (enums::CountryAlpha2::AR, enums::Currency::ARS) => Some(10000),
+        (enums::CountryAlpha2::BR, enums::Currency::BRL) => Some(100),
+        (enums::CountryAlpha2::CL, enums::Currency::CLP) => Some(100),
+        (enums::CountryAlpha2::CO, enums::Currency::COP) => Some(500000),
+        (enums::CountryAlpha2::MX, enums::Currency::MXN) => Some(1000),
+        (enums::CountryAlpha2::PE, enums::Currency::PEN) => Some(100),
+        (enums::CountryAlpha2::UY, enums::Currency::UYU) => Some(1000),
+        _ => None,
+    }
+}
+
+/// dLocal declines payments below the country's minimum only after the request is sent, checking
+/// it beforehand gives the merchant the minimum in the error
+fn validate_minimum_amount(
+    country: enums::CountryAlpha2,
+    currency: enums::Currency,
+    amount: i64,
+) -> Result<(), error_stack::Report<errors::ConnectorError>> {
+    match get_minimum_amount(country, currency) {
+        Some(minimum_amount) if amount < minimum_amount => {
+            let minimum_amount = currency
+                .to_currency_base_unit(minimum_amount)
+                .unwrap_or_else(|_| minimum_amount.to_string());
+            Err(errors::ConnectorError::RequestEncodingFailedWithReason(
+                format!(
+                "amount is below the minimum of {minimum_amount} {currency} accepted in {country}"
+            ),
+            ))?
+        }
+        _ => Ok(()),
+    }
+}
+
+/// The payer's national identity document, dLocal requires one for every payment and declines
+/// made up ones, so a payment without it fails before being sent
+fn get_payer_document(
+    document: Option<Secret<String>>,
+) -> Result<Secret<String>, error_stack::Report<errors::ConnectorError>> {
+    document.ok_or_else(|| {
+        errors::ConnectorError::MissingRequiredField {
+            field_name: "metadata.document",
+        }
+        .into()
+    })
+}
+
+/// Rejects documents which dLocal would decline for not matching the format of the country,
+/// separators such as `.`, `-` and `/` are ignored
+fn validate_document(
+    country: enums::CountryAlpha2,
+    document: &Secret<String>,
+) -> Result<(), error_stack::Report<errors::ConnectorError>> {
+    let document = document
+        .peek()
+        .chars()
+        .filter(|character| !matches!(character, '.' | '-' | '/' | ' '))
+        .collect::<String>()
+        .to_uppercase();
+    let is_numeric_with_length = |length: usize| {
+        document.len() == length && document.chars().all(|character| character.is_ascii_digit())
+    };
+    let (is_valid, expected_format) = match country {
+        // CPF or CNPJ
+        enums::CountryAlpha2::BR => (
+            is_numeric_with_length(11) || is_numeric_with_length(14),
+            "an 11 digit CPF or a 14 digit CNPJ",
+        ),
+        // DNI or CUIT/CUIL
+        enums::CountryAlpha2::AR => (
+            is_numeric_with_length(7) || is_numeric_with_length(8) || is_numeric_with_length(11),
+            "a 7 to 8 digit DNI or an 11 digit CUIT/CUIL",
+        ),
+        enums::CountryAlpha2::CL => (is_valid_rut(&document), "a RUT with a valid check digit"),
+        // CURP or RFC
+        enums::CountryAlpha2::MX => (
+            matches!(document.len(), 12 | 13 | 18)
+                && document
+                    .chars()
+                    .all(|character| character.is_ascii_alphanumeric()),
+            "an 18 character CURP or a 12 to 13 character RFC",
+        ),
+        // Cédula de ciudadanía
+        enums::CountryAlpha2::CO => ((6..=10).any(is_numeric_with_length), "a 6 to 10 digit CC"),
+        // PAN, five letters, four digits and a letter
+        enums::CountryAlpha2::IN => (
+            document.len() == 10
+                && document
+                    .chars()
+                    .enumerate()
+                    .all(|(index, character)| match index {
+                        5..=8 => character.is_ascii_digit(),
+                        _ => character.is_ascii_uppercase(),
+                    }),
+            "a 10 character PAN",
+        ),
+        _ => return Ok(()),
+    };
+    if !is_valid {
+        Err(errors::ConnectorError::RequestEncodingFailed).attach_printable(format!(
+            "document for billing country {country} must be {expected_format}"
+        ))?
+    }
+    Ok(())
+}
+
+/// The last character of a chilean RUT is a modulo 11 check digit over the preceding digits
+fn is_valid_rut(rut: &str) -> bool {
+    let mut characters = rut.chars();
+    let check_digit = characters.next_back();
+    let body = characters.as_str();
+    if !(7..=8).contains(&body.len()) || !body.chars().all(|character| character.is_ascii_digit()) {
+        return false;
+    }
+    let sum: u32 = body
+        .chars()
+        .rev()
+        .filter_map(|character| character.to_digit(10))
+        .zip((2..=7).cycle())
+        .map(|(digit, multiplier)| digit * multiplier)
+        .sum();
+    let expected_check_digit = match 11 - sum % 11 {
+        11 => Some('0'),
+        10 => Some('K'),
+        digit => char::from_digit(digit, 10),
+    };
+    check_digit == expected_check_digit
+}
+
+/// dLocal expects the payer's phone as digits only, prefixed with the country code unless the
+/// number already starts with one
+fn get_payer_phone(phone: Option<&PhoneDetails>) -> Option<Secret<String>> {
+    let phone = phone?;
+    let number = phone.number.as_ref()?.peek();
+    let number = match &phone.country_code {
+        Some(country_code) if !number.trim_start().starts_with('+') => {
+            format!("{country_code}{number}")
+        }
+        _ => number.to_string(),
+    };
+    let digits = number
+        .chars()
+        .filter(char::is_ascii_digit)
+        .collect::<String>();
+    (!digits.is_empty()).then_some(Secret::new(digits))
+}
+
+/// The billing address is only sent when it locates the payer, i.e. has a city or a zip code.
+/// The street number is taken from the second address line
+fn get_payer_address(
+    address: &hyperswitch_domain_models::address::AddressDetails,
+) -> Option<PayerAddress> {
+    let city = address.get_optional_city();
+    let zip_code = address.zip.clone();
+    (city.is_some() || zip_code.is_some()).then(|| PayerAddress {
+        state: address.state.clone(),
+        city,
+        zip_code,
+        street: address.get_optional_line1(),
+        number: address.get_optional_line2(),
+    })
+}
+
+fn get_payer_name(
+    address: &hyperswitch_domain_models::address::AddressDetails,
+) -> Option<Secret<String>> {
+    let first_name = address
+        .first_name
+        .clone()
+        .map_or("".to_string(), |first_name| first_name.peek().to_string());
+    let last_name = address
+        .last_name
+        .clone()
+        .map_or("".to_string(), |last_name| last_name.peek().to_string());
+    let name = normalize_name(&format!("{first_name} {last_name}"));
+    if !name.is_empty() {
+        Some(Secret::new(name))
+    } else {
+        None
+    }
+}
+
+/// Trims a name and collapses the whitespace between its parts, e.g. `" John  Doe "` to
+/// `"John Doe"`
+fn normalize_name(name: &str) -> String {
+    name.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// dLocal rejects card payments with a blank holder name, the payer's name is used when the card
+/// has none
+fn get_card_holder_name(
+    card_holder_name: Option<Secret<String>>,
+    payer_name: Option<&Secret<String>>,
+) -> Result<Secret<String>, error_stack::Report<errors::ConnectorError>> {
+    card_holder_name
+        .map(|holder_name| normalize_name(holder_name.peek()))
+        .filter(|holder_name| !holder_name.is_empty())
+        .map(Secret::new)
+        .or_else(|| payer_name.cloned())
+        .ok_or_else(utils::missing_field_err("card_holder_name"))
+}
+
+pub struct DlocalPaymentsSyncRequest {
+    pub authz_id: String,
+}
+
+impl TryFrom<&types::PaymentsSyncRouterData> for DlocalPaymentsSyncRequest {
+    type Error = error_stack::Report<errors::ConnectorError>;
+    fn try_from(item: &types::PaymentsSyncRouterData) -> Result<Self, Self::Error> {
+        Ok(Self {
+            authz_id: (item
+                .request
+                .connector_transaction_id
+                .get_connector_transaction_id()
+                .change_context(errors::ConnectorError::MissingConnectorTransactionID)?),
+        })
+    }
+}
+
+pub struct DlocalCompleteAuthorizeRequest {
+    pub payment_id: String,
+}
+
+impl TryFrom<&types::PaymentsCompleteAuthorizeRouterData> for DlocalCompleteAuthorizeRequest {
+    type Error = error_stack::Report<errors::ConnectorError>;
+    fn try_from(item: &types::PaymentsCompleteAuthorizeRouterData) -> Result<Self, Self::Error> {
+        Ok(Self {
+            payment_id: item
+                .request
+                .connector_transaction_id
+                .clone()
+                .ok_or(errors::ConnectorError::MissingConnectorTransactionID)?,
+        })
+    }
+}
+
+/// dLocal finishes the payment once the 3DS challenge is completed, the payment is retrieved
+/// when the shopper is back. A payment which is still `PENDING` means the challenge was
+/// abandoned and stays in `AuthenticationPending`.
+pub type DlocalCompleteAuthorizeResponse = DlocalPaymentsResponse;
+
+/// dLocal cancels the whole authorization unless an amount is sent, in some corridors the amount
+/// and currency are required
+#[derive(Default, Debug, Serialize, PartialEq)]
+pub struct DlocalPaymentsCancelRequest {
+    #[serde(skip)]
+    pub cancel_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub amount: Option<FloatMajorUnit>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub currency: Option<enums::Currency>,
+}
+
+/// Part of the payment's `connector_metadata` needed to cancel it
+#[derive(Debug, Deserialize)]
+pub struct DlocalCancelPaymentMetadata {
+    amount: Option<FloatMajorUnit>,
+    currency: Option<enums::Currency>,
+}
+
+/// dLocal can't cancel more than was authorized, payments made before the authorized amount was
+/// kept in the metadata are not checked
+fn validate_cancel_amount(
+    cancel_amount: Option<MinorUnit>,
+    authorized_amount: Option<MinorUnit>,
+) -> Result<(), error_stack::Report<errors::ConnectorError>> {
+    match cancel_amount.zip(authorized_amount) {
+        Some((cancel_amount, authorized_amount)) if cancel_amount > authorized_amount => Err(
+            errors::ConnectorError::RequestEncodingFailedWithReason(format!(
+                "cancel amount {} exceeds the authorized amount {}",
+                cancel_amount.get_amount_as_i64(),
+                authorized_amount.get_amount_as_i64()
+            ))
+            .into(),
+        ),
+        _ => Ok(()),
+    }
+}
+
+impl TryFrom<(Option<FloatMajorUnit>, &types::PaymentsCancelRouterData)>
+    for DlocalPaymentsCancelRequest
+{
+    type Error = error_stack::Report<errors::ConnectorError>;
+    fn try_from(
+        (amount, item): (Option<FloatMajorUnit>, &types::PaymentsCancelRouterData),
+    ) -> Result<Self, Self::Error> {
+        let authorized_amount = item
+            .request
+            .connector_meta
+            .clone()
+            .map(|metadata| utils::to_connector_meta::<DlocalCancelPaymentMetadata>(Some(metadata)))
+            .transpose()?
+            .and_then(|metadata| metadata.amount.zip(metadata.currency))
+            .map(|(amount, currency)| FloatMajorUnitForConnector.convert_back(amount, currency))
+            .transpose()
+            .change_context(errors::ConnectorError::RequestEncodingFailed)?;
+        validate_cancel_amount(item.request.minor_amount, authorized_amount)?;
+        Ok(Self {
+            cancel_id: item.request.connector_transaction_id.clone(),
+            amount,
+            currency: amount.and(item.request.currency),
+        })
+    }
+}
+
+#[derive(Default, Debug, Serialize, PartialEq)]
+pub struct DlocalPaymentsCaptureRequest {
+    pub authorization_id: String,
+    pub amount: FloatMajorUnit,
+    pub currency: String,
+    pub order_id: String,
+}
+
+impl TryFrom<&DlocalRouterData<&types::PaymentsCaptureRouterData>>
+    for DlocalPaymentsCaptureRequest
+{
+    type Error = error_stack::Report<errors::ConnectorError>;
+    fn try_from(
+        item: &DlocalRouterData<&types::PaymentsCaptureRouterData>,
+    ) -> Result<Self, Self::Error> {
+        let request = &item.router_data.request;
+        if request.amount_to_capture <= 0 {
+            Err(errors::ConnectorError::RequestEncodingFailed)
+                .attach_printable("amount_to_capture must be greater than zero")?
+        }
+        // Each partial capture is taken from the same authorization, the amount captured so far
+        // is counted against the authorized amount as well
+        let amount_captured = item
+            .router_data
+            .minor_amount_captured
+            .map(|amount| amount.get_amount_as_i64())
+            .unwrap_or(0);
+        if amount_captured + request.amount_to_capture > request.payment_amount {
+            Err(errors::ConnectorError::RequestEncodingFailed).attach_printable(format!(
+                "amount_to_capture {} along with the already captured amount {} exceeds the \
+                 authorized amount {}",
+                request.amount_to_capture, amount_captured, request.payment_amount
+            ))?
+        }
+        Ok(Self {
+            authorization_id: request.connector_transaction_id.clone(),
+            amount: item.amount,
+            currency: request.currency.to_string(),
+            order_id: get_dlocal_reference_id(
+                &item.router_data.connector_request_reference_id,
+                "connector_request_reference_id",
+            )?,
+        })
+    }
+}
+
+/// Sent as `X-Idempotency-Key` so that retrying the same attempt, e.g. after a timeout, doesn't
+/// create a second payment at dLocal
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct DlocalIdempotencyKey(String);
+
+impl DlocalIdempotencyKey {
+    /// The key is derived from the reference id, which stays the same across retries of an attempt
+    pub fn from_reference_id(
+        connector_request_reference_id: &str,
+    ) -> Result<Self, error_stack::Report<errors::ConnectorError>> {
+        crypto::Sha256
+            .generate_digest(connector_request_reference_id.as_bytes())
+            .change_context(errors::ConnectorError::RequestEncodingFailed)
+            .attach_printable("Failed to generate the idempotency key")
+            .map(|digest| Self(hex::encode(digest)))
+    }
+
+    pub fn get_key(&self) -> String {
+        self.0.clone()
+    }
+}
+
+// Auth Struct
+pub struct DlocalAuthType {
+    pub(super) x_login: Secret<String>,
+    pub(super) x_trans_key: Secret<String>,
+    pub(super) secret: Secret<String>,
+}
+
+/// None of the credentials are printed, the secret signs the requests and webhooks
+impl std::fmt::Debug for DlocalAuthType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DlocalAuthType")
+            .field("x_login", &format_args!("***"))
+            .field("x_trans_key", &format_args!("***"))
+            .field("secret", &format_args!("***"))
+            .finish()
+    }
+}
+
+impl std::fmt::Display for DlocalAuthType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{self:?}")
+    }
+}
+
+impl TryFrom<&ConnectorAuthType> for DlocalAuthType {
+    type Error = error_stack::Report<errors::ConnectorError>;
+    fn try_from(auth_type: &ConnectorAuthType) -> Result<Self, Self::Error> {
+        if let ConnectorAuthType::SignatureKey {
+            api_key,
             key1,
             api_secret,
         } = auth_type
@@ -286,6 +2007,8 @@ pub enum DlocalPaymentStatus {
     #[default]
     Pending,
     Rejected,
+    Expired,
+    Chargeback,
 }
 
 impl From<DlocalPaymentStatus> for enums::AttemptStatus {
@@ -294,24 +2017,351 @@ impl From<DlocalPaymentStatus> for enums::AttemptStatus {
             DlocalPaymentStatus::Authorized => Self::Authorized,
             DlocalPaymentStatus::Verified => Self::Authorized,
             DlocalPaymentStatus::Paid => Self::Charged,
-            DlocalPaymentStatus::Pending => Self::AuthenticationPending,
+            // Payments waiting on the customer are told apart by `get_attempt_status`
+            DlocalPaymentStatus::Pending => Self::Pending,
             DlocalPaymentStatus::Cancelled => Self::Voided,
-            DlocalPaymentStatus::Rejected => Self::AuthenticationFailed,
+            // Declines are mostly unrelated to authentication (insufficient funds, blocked card,
+            // fraud rules), the reason is in the error response
+            DlocalPaymentStatus::Rejected => Self::Failure,
+            // The shopper didn't complete the redirect or voucher payment in time
+            DlocalPaymentStatus::Expired => Self::Failure,
+            // The funds were captured before the chargeback, the dispute itself is tracked
+            // through the chargeback notification
+            DlocalPaymentStatus::Chargeback => Self::Charged,
+        }
+    }
+}
+
+/// `status_code` of a pending payment held for a manual review by dLocal's fraud prevention
+const PAYMENT_UNDER_REVIEW_STATUS_CODE: &str = "104";
+
+/// dLocal reports every payment it is waiting on as PENDING. Only a payment waiting on the
+/// customer, to complete the 3DS challenge or to pay on the redirect page or with the voucher, is
+/// pending authentication. Any other pending payment, including one under review, is processing.
+fn get_attempt_status(
+    status: DlocalPaymentStatus,
+    status_code: Option<&str>,
+    is_awaiting_customer: bool,
+) -> enums::AttemptStatus {
+    match status {
+        DlocalPaymentStatus::Pending
+            if is_awaiting_customer && status_code != Some(PAYMENT_UNDER_REVIEW_STATUS_CODE) =>
+        {
+            enums::AttemptStatus::AuthenticationPending
         }
+        status => enums::AttemptStatus::from(status),
     }
 }
 
 #[derive(Eq, Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct ThreeDSecureResData {
     pub redirect_url: Option<Url>,
+    /// Issuers stepping up to a 3DS challenge expect a POST of the `redirect_params`, e.g. the
+    /// `creq`, instead of a GET of the redirect url. Some acquirers name these `method` and
+    /// `parameters`
+    #[serde(alias = "method")]
+    pub redirect_method: Option<Method>,
+    #[serde(alias = "parameters")]
+    pub redirect_params: Option<HashMap<String, String>>,
+    pub eci: Option<String>,
+    pub three_dsecure_version: Option<String>,
 }
 
-#[derive(Debug, Default, Eq, Clone, PartialEq, Serialize, Deserialize)]
+impl ThreeDSecureResData {
+    fn get_redirect_form(self) -> Option<RedirectForm> {
+        let redirect_url = self.redirect_url?;
+        Some(match (self.redirect_method, self.redirect_params) {
+            (Some(Method::Post), Some(redirect_params)) => RedirectForm::Form {
+                endpoint: redirect_url.to_string(),
+                method: Method::Post,
+                form_fields: redirect_params,
+            },
+            (redirect_method, _) => {
+                RedirectForm::from((redirect_url, redirect_method.unwrap_or(Method::Get)))
+            }
+        })
+    }
+}
+
+/// Redirect based payment methods (e.g. Pix) return the redirect url at the top level while card
+/// payments return it as part of the 3DS data
+fn get_redirection_data(
+    three_dsecure: Option<ThreeDSecureResData>,
+    redirect_url: Option<Url>,
+) -> Option<RedirectForm> {
+    three_dsecure
+        .and_then(ThreeDSecureResData::get_redirect_form)
+        .or_else(|| {
+            redirect_url.map(|redirect_url| RedirectForm::from((redirect_url, Method::Get)))
+        })
+}
+
+const DLOCAL_DATE_TIME_FORMAT: &str =
+    "[year]-[month]-[day]T[hour]:[minute]:[second].[subsecond][offset_hour sign:mandatory][offset_minute]";
+
+/// dLocal returns dates as `2024-05-21T23:59:59.000+0000`, and as RFC 3339 on some endpoints,
+/// converted to UTC
+fn parse_date_time(date_time: &str) -> Option<time::PrimitiveDateTime> {
+    time::format_description::parse(DLOCAL_DATE_TIME_FORMAT)
+        .ok()
+        .and_then(|format| time::OffsetDateTime::parse(date_time, &format).ok())
+        .or_else(|| time::OffsetDateTime::parse(date_time, &Rfc3339).ok())
+        .map(|date_time| date_time::convert_to_pdt(date_time.to_offset(time::UtcOffset::UTC)))
+}
+
+/// The creation date is informational, an unparseable date is dropped rather than failing the
+/// response
+fn deserialize_date_time<'de, D>(
+    deserializer: D,
+) -> Result<Option<time::PrimitiveDateTime>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    Option::<String>::deserialize(deserializer)
+        .map(|date_time| date_time.as_deref().and_then(parse_date_time))
+}
+
+#[derive(Eq, Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DlocalTicketData {
+    pub number: Option<String>,
+    pub barcode: Option<String>,
+    pub expiration_date: Option<String>,
+    pub image_url: Option<Url>,
+    pub url: Option<Url>,
+}
+
+impl DlocalTicketData {
+    fn get_voucher_next_step_data(&self) -> Option<VoucherNextStepData> {
+        let reference = self.barcode.clone().or_else(|| self.number.clone())?;
+        // The voucher is still usable without its expiry so an unparseable value is dropped
+        // rather than failing the payment
+        let expires_at = self
+            .expiration_date
+            .as_deref()
+            .and_then(parse_date_time)
+            .map(|expiry| utils::get_timestamp_in_milliseconds(&expiry));
+        Some(VoucherNextStepData {
+            expires_at,
+            reference,
+            download_url: self.image_url.clone(),
+            instructions_url: self.url.clone(),
+        })
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
 pub struct DlocalPaymentsResponse {
     status: DlocalPaymentStatus,
     id: String,
     three_dsecure: Option<ThreeDSecureResData>,
+    redirect_url: Option<Url>,
+    ticket: Option<DlocalTicketData>,
+    card: Option<DlocalCardResponse>,
     order_id: Option<String>,
+    status_code: Option<String>,
+    status_detail: Option<String>,
+    installments: Option<DlocalInstallmentsResponse>,
+    amount: Option<FloatMajorUnit>,
+    currency: Option<enums::Currency>,
+    #[serde(
+        default,
+        deserialize_with = "deserialize_date_time",
+        serialize_with = "common_utils::custom_serde::iso8601::option::serialize"
+    )]
+    created_date: Option<time::PrimitiveDateTime>,
+}
+
+/// Installments plan applied to a card payment, the total includes the surcharge dLocal adds
+/// for paying in installments
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DlocalInstallmentsResponse {
+    id: Option<String>,
+    installments: Option<String>,
+    installment_amount: Option<FloatMajorUnit>,
+    total_amount: Option<FloatMajorUnit>,
+}
+
+/// Payment method details kept on the attempt, the card is only described by its brand and last
+/// four digits so that no PAN ends up in `connector_metadata`
+#[derive(Debug, Default, Serialize)]
+pub struct DlocalConnectorMetadata {
+    #[serde(flatten, skip_serializing_if = "Option::is_none")]
+    voucher: Option<VoucherNextStepData>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    installments: Option<DlocalInstallmentsResponse>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    card: Option<DlocalCardMetadata>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    three_dsecure: Option<DlocalThreeDSecureMetadata>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    amount: Option<FloatMajorUnit>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    currency: Option<enums::Currency>,
+    /// When dLocal created the payment, for reconciling with the merchant's ledger
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "common_utils::custom_serde::iso8601::option::serialize"
+    )]
+    created_date: Option<time::PrimitiveDateTime>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DlocalCardMetadata {
+    brand: Option<String>,
+    last4: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    first6: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    issuer_country: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DlocalThreeDSecureMetadata {
+    eci: Option<String>,
+    three_dsecure_version: Option<String>,
+}
+
+impl DlocalPaymentsResponse {
+    fn get_connector_metadata(
+        &self,
+    ) -> CustomResult<Option<serde_json::Value>, errors::ConnectorError> {
+        let metadata = DlocalConnectorMetadata {
+            voucher: self
+                .ticket
+                .as_ref()
+                .and_then(DlocalTicketData::get_voucher_next_step_data),
+            installments: self.installments.clone(),
+            card: self
+                .card
+                .as_ref()
+                .filter(|card| {
+                    card.brand.is_some()
+                        || card.last4.is_some()
+                        || card.first6.is_some()
+                        || card.issuer_country.is_some()
+                })
+                .map(|card| DlocalCardMetadata {
+                    brand: card.brand.clone(),
+                    last4: card.last4.clone(),
+                    first6: card.first6.clone(),
+                    issuer_country: card.issuer_country.clone(),
+                }),
+            three_dsecure: self
+                .three_dsecure
+                .as_ref()
+                .filter(|three_dsecure| {
+                    three_dsecure.eci.is_some() || three_dsecure.three_dsecure_version.is_some()
+                })
+                .map(|three_dsecure| DlocalThreeDSecureMetadata {
+                    eci: three_dsecure.eci.clone(),
+                    three_dsecure_version: three_dsecure.three_dsecure_version.clone(),
+                }),
+            amount: self.amount,
+            currency: self.currency,
+            created_date: self.created_date,
+        };
+        let is_empty = metadata.voucher.is_none()
+            && metadata.installments.is_none()
+            && metadata.card.is_none()
+            && metadata.three_dsecure.is_none()
+            && metadata.amount.is_none()
+            && metadata.currency.is_none()
+            && metadata.created_date.is_none();
+        (!is_empty)
+            .then(|| metadata.encode_to_value())
+            .transpose()
+            .change_context(errors::ConnectorError::ResponseHandlingFailed)
+    }
+
+    /// A 3DS challenge, a redirect page or a voucher is left for the customer to complete
+    fn is_awaiting_customer(&self) -> bool {
+        self.three_dsecure
+            .as_ref()
+            .is_some_and(|three_dsecure| three_dsecure.redirect_url.is_some())
+            || self.redirect_url.is_some()
+            || self.ticket.is_some()
+    }
+
+    fn get_error_response(&self, http_code: u16) -> Option<ErrorResponse> {
+        get_rejected_payment_error_response(
+            &self.status,
+            &self.id,
+            self.status_code.as_ref(),
+            self.status_detail.as_ref(),
+            http_code,
+        )
+    }
+}
+
+/// Rejected payments carry the decline reason in `status_code` and `status_detail`
+fn get_rejected_payment_error_response(
+    status: &DlocalPaymentStatus,
+    id: &str,
+    status_code: Option<&String>,
+    status_detail: Option<&String>,
+    http_code: u16,
+) -> Option<ErrorResponse> {
+    (*status == DlocalPaymentStatus::Rejected).then(|| ErrorResponse {
+        code: status_code
+            .cloned()
+            .unwrap_or(consts::NO_ERROR_CODE.to_string()),
+        message: status_detail
+            .cloned()
+            .unwrap_or(consts::NO_ERROR_MESSAGE.to_string()),
+        reason: status_detail.cloned(),
+        status_code: http_code,
+        attempt_status: None,
+        connector_transaction_id: Some(id.to_string()),
+    })
+}
+
+#[derive(Debug, Default, Eq, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DlocalCardResponse {
+    card_id: Option<String>,
+    network_tx_reference: Option<String>,
+    brand: Option<String>,
+    last4: Option<String>,
+    #[serde(alias = "bin")]
+    first6: Option<String>,
+    issuer_country: Option<String>,
+}
+
+/// The merchant's order id, dLocal's own payment id when the response leaves the order id out
+fn get_connector_response_reference_id(order_id: Option<String>, id: &str) -> Option<String> {
+    Some(order_id.unwrap_or_else(|| id.to_string()))
+}
+
+/// The network reference of an approved card payment, used to chain merchant initiated payments
+fn get_network_txn_id(card: Option<&DlocalCardResponse>) -> Option<String> {
+    card.and_then(|card| card.network_tx_reference.clone())
+}
+
+/// dLocal has no way of raising the amount of an authorization, a higher amount needs a new
+/// payment. This is surfaced on authorized payments so the router doesn't offer it
+fn get_incremental_authorization_allowed(status: &DlocalPaymentStatus) -> Option<bool> {
+    (*status == DlocalPaymentStatus::Authorized).then_some(false)
+}
+
+/// Cards of payments set up for off session usage are saved with `save: true`, unlike
+/// installments which only split the charge of a single payment
+fn should_save_card(setup_future_usage: Option<enums::FutureUsage>) -> bool {
+    setup_future_usage == Some(enums::FutureUsage::OffSession)
+}
+
+/// dLocal only returns the `card_id` of saved cards, it is used as the mandate of the subsequent
+/// merchant initiated payments
+fn get_mandate_reference(
+    card: Option<DlocalCardResponse>,
+    setup_future_usage: Option<enums::FutureUsage>,
+) -> Option<MandateReference> {
+    card.and_then(|card| card.card_id)
+        .filter(|_| should_save_card(setup_future_usage))
+        .map(|card_id| MandateReference {
+            connector_mandate_id: Some(card_id),
+            payment_method_id: None,
+            mandate_metadata: None,
+            connector_mandate_request_reference_id: None,
+        })
 }
 
 impl<F, T> TryFrom<ResponseRouterData<F, DlocalPaymentsResponse, T, PaymentsResponseData>>
@@ -321,84 +2371,234 @@ impl<F, T> TryFrom<ResponseRouterData<F, DlocalPaymentsResponse, T, PaymentsResp
     fn try_from(
         item: ResponseRouterData<F, DlocalPaymentsResponse, T, PaymentsResponseData>,
     ) -> Result<Self, Self::Error> {
-        let redirection_data = item
-            .response
-            .three_dsecure
-            .and_then(|three_secure_data| three_secure_data.redirect_url)
-            .map(|redirect_url| RedirectForm::from((redirect_url, Method::Get)));
-
-        let response = PaymentsResponseData::TransactionResponse {
-            resource_id: ResponseId::ConnectorTransactionId(item.response.id.clone()),
-            redirection_data: Box::new(redirection_data),
-            mandate_reference: Box::new(None),
-            connector_metadata: None,
-            network_txn_id: None,
-            connector_response_reference_id: item.response.order_id.clone(),
-            incremental_authorization_allowed: None,
-            charges: None,
+        Self::try_from((item, None))
+    }
+}
+
+impl<F, T>
+    TryFrom<(
+        ResponseRouterData<F, DlocalPaymentsResponse, T, PaymentsResponseData>,
+        Option<enums::FutureUsage>,
+    )> for RouterData<F, T, PaymentsResponseData>
+{
+    type Error = error_stack::Report<errors::ConnectorError>;
+    fn try_from(
+        (item, setup_future_usage): (
+            ResponseRouterData<F, DlocalPaymentsResponse, T, PaymentsResponseData>,
+            Option<enums::FutureUsage>,
+        ),
+    ) -> Result<Self, Self::Error> {
+        let error_response = item.response.get_error_response(item.http_code);
+        let connector_metadata = item.response.get_connector_metadata()?;
+        let status = get_attempt_status(
+            item.response.status.clone(),
+            item.response.status_code.as_deref(),
+            item.response.is_awaiting_customer(),
+        );
+        let redirection_data =
+            get_redirection_data(item.response.three_dsecure, item.response.redirect_url);
+        let network_txn_id = get_network_txn_id(item.response.card.as_ref());
+        let mandate_reference = get_mandate_reference(item.response.card, setup_future_usage);
+
+        let response = match error_response {
+            Some(error_response) => Err(error_response),
+            None => Ok(PaymentsResponseData::TransactionResponse {
+                resource_id: ResponseId::ConnectorTransactionId(item.response.id.clone()),
+                redirection_data: Box::new(redirection_data),
+                mandate_reference: Box::new(mandate_reference),
+                connector_metadata,
+                network_txn_id,
+                connector_response_reference_id: get_connector_response_reference_id(
+                    item.response.order_id.clone(),
+                    &item.response.id,
+                ),
+                incremental_authorization_allowed: get_incremental_authorization_allowed(
+                    &item.response.status,
+                ),
+                charges: None,
+            }),
         };
         Ok(Self {
-            status: enums::AttemptStatus::from(item.response.status),
-            response: Ok(response),
+            status,
+            response,
             ..item.data
         })
     }
 }
 
-#[derive(Default, Debug, Clone, Serialize, Deserialize, PartialEq)]
-pub struct DlocalPaymentsSyncResponse {
-    status: DlocalPaymentStatus,
-    id: String,
-    order_id: Option<String>,
-}
+/// Response of the card verification, kept apart from [`DlocalPaymentsResponse`] so that a
+/// verified card isn't mistaken for an authorized payment
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(transparent)]
+pub struct DlocalSetupMandateResponse(DlocalPaymentsResponse);
 
-impl<F, T> TryFrom<ResponseRouterData<F, DlocalPaymentsSyncResponse, T, PaymentsResponseData>>
-    for RouterData<F, T, PaymentsResponseData>
+impl
+    TryFrom<
+        ResponseRouterData<
+            SetupMandate,
+            DlocalSetupMandateResponse,
+            SetupMandateRequestData,
+            PaymentsResponseData,
+        >,
+    > for RouterData<SetupMandate, SetupMandateRequestData, PaymentsResponseData>
 {
     type Error = error_stack::Report<errors::ConnectorError>;
     fn try_from(
-        item: ResponseRouterData<F, DlocalPaymentsSyncResponse, T, PaymentsResponseData>,
+        item: ResponseRouterData<
+            SetupMandate,
+            DlocalSetupMandateResponse,
+            SetupMandateRequestData,
+            PaymentsResponseData,
+        >,
     ) -> Result<Self, Self::Error> {
-        Ok(Self {
-            status: enums::AttemptStatus::from(item.response.status),
-            response: Ok(PaymentsResponseData::TransactionResponse {
-                resource_id: ResponseId::ConnectorTransactionId(item.response.id.clone()),
-                redirection_data: Box::new(None),
-                mandate_reference: Box::new(None),
-                connector_metadata: None,
-                network_txn_id: None,
-                connector_response_reference_id: item.response.order_id.clone(),
+        let DlocalSetupMandateResponse(response) = item.response;
+        let error_response = response.get_error_response(item.http_code);
+        let connector_metadata = response.get_connector_metadata()?;
+        let status = match response.status {
+            DlocalPaymentStatus::Verified
+            | DlocalPaymentStatus::Authorized
+            | DlocalPaymentStatus::Paid => enums::AttemptStatus::Charged,
+            DlocalPaymentStatus::Pending => enums::AttemptStatus::AuthenticationPending,
+            DlocalPaymentStatus::Cancelled => enums::AttemptStatus::Voided,
+            DlocalPaymentStatus::Rejected | DlocalPaymentStatus::Expired => {
+                enums::AttemptStatus::Failure
+            }
+            DlocalPaymentStatus::Chargeback => enums::AttemptStatus::Charged,
+        };
+        let redirection_data = get_redirection_data(response.three_dsecure, None);
+        let network_txn_id = get_network_txn_id(response.card.as_ref());
+        let mandate_reference =
+            get_mandate_reference(response.card, Some(enums::FutureUsage::OffSession));
+        let response = match error_response {
+            Some(error_response) => Err(error_response),
+            None => Ok(PaymentsResponseData::TransactionResponse {
+                connector_response_reference_id: get_connector_response_reference_id(
+                    response.order_id,
+                    &response.id,
+                ),
+                resource_id: ResponseId::ConnectorTransactionId(response.id),
+                redirection_data: Box::new(redirection_data),
+                mandate_reference: Box::new(mandate_reference),
+                connector_metadata,
+                network_txn_id,
                 incremental_authorization_allowed: None,
                 charges: None,
             }),
+        };
+        Ok(Self {
+            status,
+            response,
             ..item.data
         })
     }
 }
 
+/// Status of a single capture, unlike the payment status a pending capture is not awaiting
+/// authentication
+fn get_capture_status(status: &DlocalPaymentStatus) -> enums::AttemptStatus {
+    match status {
+        DlocalPaymentStatus::Paid | DlocalPaymentStatus::Chargeback => {
+            enums::AttemptStatus::Charged
+        }
+        DlocalPaymentStatus::Authorized
+        | DlocalPaymentStatus::Verified
+        | DlocalPaymentStatus::Pending => enums::AttemptStatus::Pending,
+        DlocalPaymentStatus::Rejected
+        | DlocalPaymentStatus::Cancelled
+        | DlocalPaymentStatus::Expired => enums::AttemptStatus::CaptureFailed,
+    }
+}
+
+impl MultipleCaptureSyncResponse for DlocalPaymentsResponse {
+    fn get_connector_capture_id(&self) -> String {
+        self.id.clone()
+    }
+
+    fn get_capture_attempt_status(&self) -> enums::AttemptStatus {
+        get_capture_status(&self.status)
+    }
+
+    fn is_capture_response(&self) -> bool {
+        true
+    }
+
+    fn get_connector_reference_id(&self) -> Option<String> {
+        self.order_id.clone()
+    }
+
+    fn get_amount_captured(&self) -> Result<Option<MinorUnit>, error_stack::Report<ParsingError>> {
+        self.amount
+            .zip(self.currency)
+            .map(|(amount, currency)| FloatMajorUnitForConnector.convert_back(amount, currency))
+            .transpose()
+    }
+}
+
+/// dLocal creates a new payment for every capture, its id is the capture id while the
+/// authorization keeps its own id for further partial captures
 #[derive(Default, Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct DlocalPaymentsCaptureResponse {
     status: DlocalPaymentStatus,
     id: String,
+    card: Option<DlocalCardResponse>,
     order_id: Option<String>,
+    status_code: Option<String>,
+    status_detail: Option<String>,
 }
 
-impl<F, T> TryFrom<ResponseRouterData<F, DlocalPaymentsCaptureResponse, T, PaymentsResponseData>>
-    for RouterData<F, T, PaymentsResponseData>
+impl TryFrom<PaymentsCaptureResponseRouterData<DlocalPaymentsCaptureResponse>>
+    for types::PaymentsCaptureRouterData
 {
     type Error = error_stack::Report<errors::ConnectorError>;
     fn try_from(
-        item: ResponseRouterData<F, DlocalPaymentsCaptureResponse, T, PaymentsResponseData>,
+        item: PaymentsCaptureResponseRouterData<DlocalPaymentsCaptureResponse>,
     ) -> Result<Self, Self::Error> {
+        let amount_captured = item
+            .data
+            .minor_amount_captured
+            .map(|amount| amount.get_amount_as_i64())
+            .unwrap_or(0)
+            + item.data.request.amount_to_capture;
+        // dLocal reports a captured payment as PAID irrespective of the captured amount
+        let status = match get_capture_status(&item.response.status) {
+            enums::AttemptStatus::Charged if amount_captured < item.data.request.payment_amount => {
+                enums::AttemptStatus::PartialCharged
+            }
+            status => status,
+        };
+        if item.response.status == DlocalPaymentStatus::Rejected {
+            return Ok(Self {
+                status,
+                response: Err(ErrorResponse {
+                    code: item
+                        .response
+                        .status_code
+                        .unwrap_or(consts::NO_ERROR_CODE.to_string()),
+                    message: item
+                        .response
+                        .status_detail
+                        .clone()
+                        .unwrap_or(consts::NO_ERROR_MESSAGE.to_string()),
+                    reason: item.response.status_detail,
+                    status_code: item.http_code,
+                    attempt_status: None,
+                    connector_transaction_id: Some(item.response.id),
+                }),
+                ..item.data
+            });
+        }
         Ok(Self {
-            status: enums::AttemptStatus::from(item.response.status),
+            status,
             response: Ok(PaymentsResponseData::TransactionResponse {
                 resource_id: ResponseId::ConnectorTransactionId(item.response.id.clone()),
                 redirection_data: Box::new(None),
                 mandate_reference: Box::new(None),
                 connector_metadata: None,
-                network_txn_id: None,
-                connector_response_reference_id: item.response.order_id.clone(),
+                network_txn_id: get_network_txn_id(item.response.card.as_ref()),
+                connector_response_reference_id: get_connector_response_reference_id(
+                    item.response.order_id.clone(),
+                    &item.response.id,
+                ),
                 incremental_authorization_allowed: None,
                 charges: None,
             }),
@@ -407,27 +2607,62 @@ impl<F, T> TryFrom<ResponseRouterData<F, DlocalPaymentsCaptureResponse, T, Payme
     }
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct DlocalPaymentsCancelResponse {
+    id: String,
     status: DlocalPaymentStatus,
-    order_id: String,
+    order_id: Option<String>,
+    amount: Option<FloatMajorUnit>,
+    currency: Option<enums::Currency>,
 }
 
-impl<F, T> TryFrom<ResponseRouterData<F, DlocalPaymentsCancelResponse, T, PaymentsResponseData>>
-    for RouterData<F, T, PaymentsResponseData>
-{
-    type Error = error_stack::Report<errors::ConnectorError>;
+impl DlocalPaymentsCancelResponse {
+    /// Cancelling less than the authorized amount leaves the rest of the authorization to be
+    /// captured, so a partial cancel keeps the attempt authorized
+    fn get_attempt_status(
+        &self,
+        authorized_amount: Option<MinorUnit>,
+    ) -> CustomResult<enums::AttemptStatus, errors::ConnectorError> {
+        let cancelled_amount = self
+            .amount
+            .zip(self.currency)
+            .map(|(amount, currency)| FloatMajorUnitForConnector.convert_back(amount, currency))
+            .transpose()
+            .change_context(errors::ConnectorError::ResponseHandlingFailed)?;
+        Ok(
+            match (&self.status, cancelled_amount.zip(authorized_amount)) {
+                (DlocalPaymentStatus::Cancelled, Some((cancelled_amount, authorized_amount)))
+                    if cancelled_amount < authorized_amount =>
+                {
+                    enums::AttemptStatus::Authorized
+                }
+                (status, _) => enums::AttemptStatus::from(status.clone()),
+            },
+        )
+    }
+}
+
+impl TryFrom<PaymentsCancelResponseRouterData<DlocalPaymentsCancelResponse>>
+    for types::PaymentsCancelRouterData
+{
+    type Error = error_stack::Report<errors::ConnectorError>;
     fn try_from(
-        item: ResponseRouterData<F, DlocalPaymentsCancelResponse, T, PaymentsResponseData>,
+        item: PaymentsCancelResponseRouterData<DlocalPaymentsCancelResponse>,
     ) -> Result<Self, Self::Error> {
         Ok(Self {
-            status: enums::AttemptStatus::from(item.response.status),
+            status: item
+                .response
+                .get_attempt_status(item.data.request.minor_amount)?,
             response: Ok(PaymentsResponseData::TransactionResponse {
-                resource_id: ResponseId::ConnectorTransactionId(item.response.order_id.clone()),
+                connector_response_reference_id: get_connector_response_reference_id(
+                    item.response.order_id,
+                    &item.response.id,
+                ),
+                resource_id: ResponseId::ConnectorTransactionId(item.response.id),
                 redirection_data: Box::new(None),
                 mandate_reference: Box::new(None),
                 connector_metadata: None,
                 network_txn_id: None,
-                connector_response_reference_id: Some(item.response.order_id.clone()),
                 incremental_authorization_allowed: None,
                 charges: None,
             }),
@@ -439,10 +2674,40 @@ impl<F, T> TryFrom<ResponseRouterData<F, DlocalPaymentsCancelResponse, T, Paymen
 // REFUND :
 #[derive(Default, Debug, Serialize)]
 pub struct DlocalRefundRequest {
-    pub amount: String,
+    pub amount: FloatMajorUnit,
     pub payment_id: String,
     pub currency: enums::Currency,
     pub id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub notification_url: Option<String>,
+}
+
+/// Part of the payment's `connector_metadata` needed to refund it
+#[derive(Debug, Deserialize)]
+pub struct DlocalRefundPaymentMetadata {
+    currency: Option<enums::Currency>,
+}
+
+/// dLocal rejects refunds of no amount and refunds in a currency other than the one the payment
+/// was made in, payments made before their currency was kept in the metadata are not checked
+fn validate_refund(
+    refund_amount: i64,
+    currency: enums::Currency,
+    payment_currency: Option<enums::Currency>,
+) -> Result<(), error_stack::Report<errors::ConnectorError>> {
+    if refund_amount <= 0 {
+        Err(errors::ConnectorError::RequestEncodingFailedWithReason(
+            "refund amount must be greater than zero".to_string(),
+        ))?
+    }
+    match payment_currency {
+        Some(payment_currency) if payment_currency != currency => Err(
+            errors::ConnectorError::RequestEncodingFailedWithReason(format!(
+                "refund currency {currency} does not match the payment currency {payment_currency}"
+            )),
+        )?,
+        _ => Ok(()),
+    }
 }
 
 impl<F> TryFrom<&DlocalRouterData<&types::RefundsRouterData<F>>> for DlocalRefundRequest {
@@ -450,12 +2715,25 @@ impl<F> TryFrom<&DlocalRouterData<&types::RefundsRouterData<F>>> for DlocalRefun
     fn try_from(
         item: &DlocalRouterData<&types::RefundsRouterData<F>>,
     ) -> Result<Self, Self::Error> {
-        let amount_to_refund = item.router_data.request.refund_amount.to_string();
+        let payment_currency = item
+            .router_data
+            .request
+            .connector_metadata
+            .clone()
+            .map(|metadata| utils::to_connector_meta::<DlocalRefundPaymentMetadata>(Some(metadata)))
+            .transpose()?
+            .and_then(|metadata| metadata.currency);
+        validate_refund(
+            item.router_data.request.refund_amount,
+            item.router_data.request.currency,
+            payment_currency,
+        )?;
         Ok(Self {
-            amount: amount_to_refund,
+            amount: item.amount,
             payment_id: item.router_data.request.connector_transaction_id.clone(),
             currency: item.router_data.request.currency,
-            id: item.router_data.request.refund_id.clone(),
+            id: get_dlocal_reference_id(&item.router_data.request.refund_id, "refund_id")?,
+            notification_url: item.router_data.request.webhook_url.clone(),
         })
     }
 }
@@ -471,13 +2749,15 @@ pub enum RefundStatus {
     Cancelled,
 }
 
+/// A rejected refund, e.g. of a payment which was charged back, is final while a cancelled refund
+/// may have been cancelled on the merchant's request and is left for review
 impl From<RefundStatus> for enums::RefundStatus {
     fn from(item: RefundStatus) -> Self {
         match item {
             RefundStatus::Success => Self::Success,
             RefundStatus::Pending => Self::Pending,
-            RefundStatus::Rejected => Self::ManualReview,
-            RefundStatus::Cancelled => Self::Failure,
+            RefundStatus::Rejected => Self::Failure,
+            RefundStatus::Cancelled => Self::ManualReview,
         }
     }
 }
@@ -486,6 +2766,84 @@ impl From<RefundStatus> for enums::RefundStatus {
 pub struct RefundResponse {
     pub id: String,
     pub status: RefundStatus,
+    pub amount: Option<FloatMajorUnit>,
+    pub currency: Option<enums::Currency>,
+    #[serde(
+        default,
+        deserialize_with = "deserialize_date_time",
+        serialize_with = "common_utils::custom_serde::iso8601::option::serialize"
+    )]
+    pub created_date: Option<time::PrimitiveDateTime>,
+    pub status_code: Option<String>,
+    pub status_detail: Option<String>,
+}
+
+impl RefundResponse {
+    /// Rejected refunds carry the reason in `status_code` and `status_detail`
+    fn get_error_response(&self, http_code: u16) -> Option<ErrorResponse> {
+        matches!(self.status, RefundStatus::Rejected).then(|| ErrorResponse {
+            code: self
+                .status_code
+                .clone()
+                .unwrap_or(consts::NO_ERROR_CODE.to_string()),
+            message: self
+                .status_detail
+                .clone()
+                .unwrap_or(consts::NO_ERROR_MESSAGE.to_string()),
+            reason: self.status_detail.clone(),
+            status_code: http_code,
+            attempt_status: None,
+            connector_transaction_id: Some(self.id.clone()),
+        })
+    }
+
+    /// Amount and currency dLocal refunded, tells apart the partial refunds of a payment
+    fn get_integrity_object(
+        &self,
+    ) -> CustomResult<Option<RefundIntegrityObject>, errors::ConnectorError> {
+        self.amount
+            .zip(self.currency)
+            .map(|(amount, currency)| {
+                FloatMajorUnitForConnector
+                    .convert_back(amount, currency)
+                    .map(|refund_amount| RefundIntegrityObject {
+                        currency,
+                        refund_amount,
+                    })
+                    .change_context(errors::ConnectorError::AmountConversionFailed)
+            })
+            .transpose()
+    }
+
+    /// Only rejections dLocal classifies as temporary are retryable, a retried refund of a
+    /// cancelled or charged back payment is rejected again
+    pub fn get_retry_classification(&self) -> DlocalRefundRetry {
+        let code = self.status_code.clone();
+        let is_temporary_rejection = self
+            .status_code
+            .as_deref()
+            .and_then(|code| code.parse::<i32>().ok())
+            .and_then(DlocalErrorCategory::from_code)
+            .is_some_and(|category| category.get_attempt_status().is_none());
+        match self.status {
+            RefundStatus::Pending => DlocalRefundRetry::Retryable { code },
+            RefundStatus::Rejected if is_temporary_rejection => {
+                DlocalRefundRetry::Retryable { code }
+            }
+            RefundStatus::Success | RefundStatus::Rejected | RefundStatus::Cancelled => {
+                DlocalRefundRetry::Terminal { code }
+            }
+        }
+    }
+}
+
+/// Whether retrying a refund could change its outcome, with dLocal's refund status code
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DlocalRefundRetry {
+    /// Still pending, or rejected for a reason that may clear up such as an unavailable issuer
+    Retryable { code: Option<String> },
+    /// Refunded, cancelled or rejected for good, e.g. because the payment was charged back
+    Terminal { code: Option<String> },
 }
 
 impl TryFrom<RefundsResponseRouterData<Execute, RefundResponse>>
@@ -495,14 +2853,22 @@ impl TryFrom<RefundsResponseRouterData<Execute, RefundResponse>>
     fn try_from(
         item: RefundsResponseRouterData<Execute, RefundResponse>,
     ) -> Result<Self, Self::Error> {
+        let integrity_object = item.response.get_integrity_object()?;
+        let error_response = item.response.get_error_response(item.http_code);
         let refund_status = enums::RefundStatus::from(item.response.status);
-        Ok(Self {
-            response: Ok(RefundsResponseData {
-                connector_refund_id: item.response.id,
-                refund_status,
-            }),
+        let mut router_data = Self {
+            response: match error_response {
+                Some(error_response) => Err(error_response),
+                None => Ok(RefundsResponseData {
+                    connector_refund_id: item.response.id,
+                    refund_status,
+                }),
+            },
             ..item.data
-        })
+        };
+        router_data.request.integrity_object =
+            integrity_object.or(router_data.request.integrity_object);
+        Ok(router_data)
     }
 }
 
@@ -529,39 +2895,3404 @@ impl TryFrom<RefundsResponseRouterData<RSync, RefundResponse>> for types::Refund
     fn try_from(
         item: RefundsResponseRouterData<RSync, RefundResponse>,
     ) -> Result<Self, Self::Error> {
+        let integrity_object = item.response.get_integrity_object()?;
+        let error_response = item.response.get_error_response(item.http_code);
         let refund_status = enums::RefundStatus::from(item.response.status);
+        let mut router_data = Self {
+            response: match error_response {
+                Some(error_response) => Err(error_response),
+                None => Ok(RefundsResponseData {
+                    connector_refund_id: item.response.id,
+                    refund_status,
+                }),
+            },
+            ..item.data
+        };
+        router_data.request.integrity_object =
+            integrity_object.or(router_data.request.integrity_object);
+        Ok(router_data)
+    }
+}
+
+// PAYOUTS :
+/// Payouts are created on hold and only sent to the beneficiary's bank once confirmed
+#[cfg(feature = "payouts")]
+#[derive(Debug, Serialize)]
+pub struct DlocalPayoutCreateRequest {
+    external_id: String,
+    amount: FloatMajorUnit,
+    currency: enums::Currency,
+    country: enums::CountryAlpha2,
+    beneficiary: DlocalPayoutBeneficiary,
+    on_hold: bool,
+}
+
+#[cfg(feature = "payouts")]
+#[derive(Debug, Serialize)]
+pub struct DlocalPayoutBeneficiary {
+    first_name: Secret<String>,
+    last_name: Option<Secret<String>>,
+    email: Option<Email>,
+    document: Secret<String>,
+    bank_account: DlocalPayoutBankAccount,
+}
+
+#[cfg(feature = "payouts")]
+#[derive(Debug, Serialize)]
+pub struct DlocalPayoutBankAccount {
+    bank_name: Option<String>,
+    branch: Option<String>,
+    account: Secret<String>,
+    pix_key: Option<Secret<String>>,
+}
+
+#[cfg(feature = "payouts")]
+impl TryFrom<&DlocalRouterData<&PayoutsRouterData<PoCreate>>> for DlocalPayoutCreateRequest {
+    type Error = error_stack::Report<errors::ConnectorError>;
+    fn try_from(
+        item: &DlocalRouterData<&PayoutsRouterData<PoCreate>>,
+    ) -> Result<Self, Self::Error> {
+        let country = item.router_data.get_billing_country()?;
+        match item.router_data.get_payout_method_data()? {
+            PayoutMethodData::Bank(Bank::Pix(pix_data)) => {
+                // CPF or CNPJ of the beneficiary, dLocal rejects payouts to unregistered documents
+                let document =
+                    pix_data
+                        .tax_id
+                        .ok_or(errors::ConnectorError::MissingRequiredField {
+                            field_name: "payout_method_data.bank.tax_id",
+                        })?;
+                validate_document(country, &document)?;
+                Ok(Self {
+                    external_id: get_dlocal_reference_id(
+                        &item.router_data.connector_request_reference_id,
+                        "connector_request_reference_id",
+                    )?,
+                    amount: item.amount,
+                    currency: item.router_data.request.destination_currency,
+                    country,
+                    beneficiary: DlocalPayoutBeneficiary {
+                        first_name: item.router_data.get_billing_first_name()?,
+                        last_name: item.router_data.get_optional_billing_last_name(),
+                        email: item.router_data.get_optional_billing_email(),
+                        document,
+                        bank_account: DlocalPayoutBankAccount {
+                            bank_name: pix_data.bank_name,
+                            branch: pix_data.bank_branch,
+                            account: pix_data.bank_account_number,
+                            pix_key: Some(pix_data.pix_key),
+                        },
+                    },
+                    on_hold: true,
+                })
+            }
+            PayoutMethodData::Bank(Bank::Ach(_))
+            | PayoutMethodData::Bank(Bank::Bacs(_))
+            | PayoutMethodData::Bank(Bank::Sepa(_))
+            | PayoutMethodData::Card(_)
+            | PayoutMethodData::Wallet(_) => Err(errors::ConnectorError::NotImplemented(
+                utils::get_unimplemented_payment_method_error_message("Dlocal"),
+            ))?,
+        }
+    }
+}
+
+#[cfg(feature = "payouts")]
+#[derive(Debug, Serialize)]
+pub struct DlocalPayoutFulfillRequest {
+    external_id: String,
+}
+
+#[cfg(feature = "payouts")]
+impl TryFrom<&PayoutsRouterData<PoFulfill>> for DlocalPayoutFulfillRequest {
+    type Error = error_stack::Report<errors::ConnectorError>;
+    fn try_from(item: &PayoutsRouterData<PoFulfill>) -> Result<Self, Self::Error> {
+        Ok(Self {
+            external_id: get_dlocal_reference_id(
+                &item.connector_request_reference_id,
+                "connector_request_reference_id",
+            )?,
+        })
+    }
+}
+
+#[cfg(feature = "payouts")]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum DlocalPayoutStatus {
+    Received,
+    Completed,
+    /// Reported by some banks days after the payout completed, once the funds were credited
+    Delivered,
+    Rejected,
+    Cancelled,
+}
+
+#[cfg(feature = "payouts")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DlocalPayoutResponse {
+    id: String,
+    status: DlocalPayoutStatus,
+    status_code: Option<String>,
+    status_detail: Option<String>,
+}
+
+#[cfg(feature = "payouts")]
+impl DlocalPayoutResponse {
+    /// A payout is received both when it's created on hold and when it's confirmed and waiting to
+    /// be credited, `received_status` tells the two apart
+    fn get_payout_response(self, received_status: enums::PayoutStatus) -> PayoutsResponseData {
+        let status = match self.status {
+            DlocalPayoutStatus::Received => received_status,
+            DlocalPayoutStatus::Completed | DlocalPayoutStatus::Delivered => {
+                enums::PayoutStatus::Success
+            }
+            DlocalPayoutStatus::Rejected => enums::PayoutStatus::Failed,
+            DlocalPayoutStatus::Cancelled => enums::PayoutStatus::Cancelled,
+        };
+        let is_rejected = self.status == DlocalPayoutStatus::Rejected;
+        PayoutsResponseData {
+            status: Some(status),
+            connector_payout_id: Some(self.id),
+            payout_eligible: None,
+            should_add_next_step_to_process_tracker: false,
+            error_code: self.status_code.filter(|_| is_rejected),
+            error_message: self.status_detail.filter(|_| is_rejected),
+        }
+    }
+}
+
+#[cfg(feature = "payouts")]
+impl TryFrom<PayoutsResponseRouterData<PoCreate, DlocalPayoutResponse>>
+    for PayoutsRouterData<PoCreate>
+{
+    type Error = error_stack::Report<errors::ConnectorError>;
+    fn try_from(
+        item: PayoutsResponseRouterData<PoCreate, DlocalPayoutResponse>,
+    ) -> Result<Self, Self::Error> {
+        Ok(Self {
+            response: Ok(item
+                .response
+                .get_payout_response(enums::PayoutStatus::RequiresFulfillment)),
+            ..item.data
+        })
+    }
+}
+
+#[cfg(feature = "payouts")]
+impl TryFrom<PayoutsResponseRouterData<PoFulfill, DlocalPayoutResponse>>
+    for PayoutsRouterData<PoFulfill>
+{
+    type Error = error_stack::Report<errors::ConnectorError>;
+    fn try_from(
+        item: PayoutsResponseRouterData<PoFulfill, DlocalPayoutResponse>,
+    ) -> Result<Self, Self::Error> {
+        Ok(Self {
+            response: Ok(item
+                .response
+                .get_payout_response(enums::PayoutStatus::Pending)),
+            ..item.data
+        })
+    }
+}
+
+#[cfg(feature = "payouts")]
+pub struct DlocalPayoutSyncRequest {
+    pub payout_id: String,
+}
+
+#[cfg(feature = "payouts")]
+impl TryFrom<&PayoutsRouterData<PoSync>> for DlocalPayoutSyncRequest {
+    type Error = error_stack::Report<errors::ConnectorError>;
+    fn try_from(item: &PayoutsRouterData<PoSync>) -> Result<Self, Self::Error> {
+        Ok(Self {
+            payout_id: item.request.connector_payout_id.clone().ok_or(
+                errors::ConnectorError::MissingRequiredField {
+                    field_name: "connector_payout_id",
+                },
+            )?,
+        })
+    }
+}
+
+#[cfg(feature = "payouts")]
+impl TryFrom<PayoutsResponseRouterData<PoSync, DlocalPayoutResponse>>
+    for PayoutsRouterData<PoSync>
+{
+    type Error = error_stack::Report<errors::ConnectorError>;
+    fn try_from(
+        item: PayoutsResponseRouterData<PoSync, DlocalPayoutResponse>,
+    ) -> Result<Self, Self::Error> {
+        Ok(Self {
+            response: Ok(item
+                .response
+                .get_payout_response(enums::PayoutStatus::Pending)),
+            ..item.data
+        })
+    }
+}
+
+#[cfg(feature = "payouts")]
+#[derive(Debug, Serialize)]
+pub struct DlocalPayoutCancelRequest {
+    pub payout_id: String,
+}
+
+#[cfg(feature = "payouts")]
+impl TryFrom<&PayoutsRouterData<PoCancel>> for DlocalPayoutCancelRequest {
+    type Error = error_stack::Report<errors::ConnectorError>;
+    fn try_from(item: &PayoutsRouterData<PoCancel>) -> Result<Self, Self::Error> {
+        Ok(Self {
+            payout_id: item.request.connector_payout_id.clone().ok_or(
+                errors::ConnectorError::MissingRequiredField {
+                    field_name: "connector_payout_id",
+                },
+            )?,
+        })
+    }
+}
+
+/// Only payouts which weren't credited yet can be cancelled, dLocal returns a completed or
+/// delivered payout as is when it's too late to cancel it
+#[cfg(feature = "payouts")]
+impl TryFrom<PayoutsResponseRouterData<PoCancel, DlocalPayoutResponse>>
+    for PayoutsRouterData<PoCancel>
+{
+    type Error = error_stack::Report<errors::ConnectorError>;
+    fn try_from(
+        item: PayoutsResponseRouterData<PoCancel, DlocalPayoutResponse>,
+    ) -> Result<Self, Self::Error> {
+        let response = match item.response.status {
+            DlocalPayoutStatus::Completed | DlocalPayoutStatus::Delivered => {
+                Err(ErrorResponse {
+                    code: item
+                        .response
+                        .status_code
+                        .unwrap_or(consts::NO_ERROR_CODE.to_string()),
+                    message: item.response.status_detail.clone().unwrap_or(
+                        "Payout was already delivered and cannot be cancelled".to_string(),
+                    ),
+                    reason: item.response.status_detail,
+                    status_code: item.http_code,
+                    attempt_status: None,
+                    connector_transaction_id: Some(item.response.id),
+                })
+            }
+            DlocalPayoutStatus::Received
+            | DlocalPayoutStatus::Rejected
+            | DlocalPayoutStatus::Cancelled => Ok(item
+                .response
+                .get_payout_response(enums::PayoutStatus::Pending)),
+        };
+        Ok(Self {
+            response,
+            ..item.data
+        })
+    }
+}
+
+// BALANCE :
+/// Merchant account balance per currency, used to reconcile settlements and payouts
+#[derive(Debug, Clone, Deserialize)]
+pub struct DlocalBalanceResponse {
+    pub balances: Vec<DlocalCurrencyBalance>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct DlocalCurrencyBalance {
+    pub currency: enums::Currency,
+    /// Funds which can be paid out or withdrawn
+    pub available_balance: FloatMajorUnit,
+    /// Funds of captured payments which dLocal hasn't settled yet
+    #[serde(default)]
+    pub pending_settlement: FloatMajorUnit,
+}
+
+// DISPUTES :
+#[derive(Debug, Clone, Serialize, Deserialize, strum::Display, PartialEq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+#[strum(serialize_all = "SCREAMING_SNAKE_CASE")]
+pub enum DlocalDisputeStatus {
+    Pending,
+    Dispute,
+    Accepted,
+    Won,
+    Reversed,
+    Lost,
+    Expired,
+    Cancelled,
+}
+
+impl From<DlocalDisputeStatus> for enums::DisputeStatus {
+    fn from(item: DlocalDisputeStatus) -> Self {
+        match item {
+            DlocalDisputeStatus::Pending => Self::DisputeOpened,
+            DlocalDisputeStatus::Dispute => Self::DisputeChallenged,
+            DlocalDisputeStatus::Accepted => Self::DisputeAccepted,
+            // A reversed chargeback returns the funds to the merchant
+            DlocalDisputeStatus::Won | DlocalDisputeStatus::Reversed => Self::DisputeWon,
+            DlocalDisputeStatus::Lost => Self::DisputeLost,
+            DlocalDisputeStatus::Expired => Self::DisputeExpired,
+            DlocalDisputeStatus::Cancelled => Self::DisputeCancelled,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DlocalDisputeResponse {
+    pub id: String,
+    pub payment_id: String,
+    pub amount: FloatMajorUnit,
+    pub currency: enums::Currency,
+    pub reason: Option<String>,
+    pub status: DlocalDisputeStatus,
+    pub status_code: Option<String>,
+    pub status_detail: Option<String>,
+    pub created_date: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DlocalAcceptDisputeRequest {
+    pub chargeback_id: String,
+}
+
+impl TryFrom<&RouterData<Accept, AcceptDisputeRequestData, AcceptDisputeResponse>>
+    for DlocalAcceptDisputeRequest
+{
+    type Error = error_stack::Report<errors::ConnectorError>;
+    fn try_from(
+        item: &RouterData<Accept, AcceptDisputeRequestData, AcceptDisputeResponse>,
+    ) -> Result<Self, Self::Error> {
+        Ok(Self {
+            chargeback_id: item.request.connector_dispute_id.clone(),
+        })
+    }
+}
+
+/// Acknowledgment of an accepted chargeback, without the chargeback details
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DlocalDisputeAcknowledgment {
+    pub id: Option<String>,
+    pub status: Option<DlocalDisputeStatus>,
+}
+
+/// dLocal answers an accepted chargeback either with the updated chargeback or with a bare
+/// acknowledgment
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(untagged)]
+pub enum DlocalAcceptDisputeResponse {
+    Chargeback(DlocalDisputeResponse),
+    Acknowledgment(DlocalDisputeAcknowledgment),
+}
+
+impl DlocalAcceptDisputeResponse {
+    fn get_connector_status(&self) -> Option<DlocalDisputeStatus> {
+        match self {
+            Self::Chargeback(chargeback) => Some(chargeback.status.clone()),
+            Self::Acknowledgment(acknowledgment) => acknowledgment.status.clone(),
+        }
+    }
+
+    /// The chargeback is accepted as soon as dLocal acknowledges it, it is only reported as lost
+    /// once dLocal says so
+    fn get_dispute_status(&self) -> enums::DisputeStatus {
+        self.get_connector_status()
+            .map(enums::DisputeStatus::from)
+            .unwrap_or(enums::DisputeStatus::DisputeAccepted)
+    }
+}
+
+impl<F, T> TryFrom<ResponseRouterData<F, DlocalAcceptDisputeResponse, T, AcceptDisputeResponse>>
+    for RouterData<F, T, AcceptDisputeResponse>
+{
+    type Error = error_stack::Report<errors::ConnectorError>;
+    fn try_from(
+        item: ResponseRouterData<F, DlocalAcceptDisputeResponse, T, AcceptDisputeResponse>,
+    ) -> Result<Self, Self::Error> {
+        Ok(Self {
+            response: Ok(AcceptDisputeResponse {
+                dispute_status: item.response.get_dispute_status(),
+                connector_status: item
+                    .response
+                    .get_connector_status()
+                    .map(|status| status.to_string()),
+            }),
+            ..item.data
+        })
+    }
+}
+
+/// Defense of a chargeback, the merchant's explanation along with the references of the
+/// documents backing it
+#[derive(Debug, Serialize)]
+pub struct DlocalSubmitEvidenceRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub comments: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub documents: Vec<String>,
+}
+
+impl TryFrom<&RouterData<Evidence, SubmitEvidenceRequestData, SubmitEvidenceResponse>>
+    for DlocalSubmitEvidenceRequest
+{
+    type Error = error_stack::Report<errors::ConnectorError>;
+    fn try_from(
+        item: &RouterData<Evidence, SubmitEvidenceRequestData, SubmitEvidenceResponse>,
+    ) -> Result<Self, Self::Error> {
+        let evidence = &item.request;
+        let comments = [
+            &evidence.uncategorized_text,
+            &evidence.product_description,
+            &evidence.cancellation_rebuttal,
+            &evidence.refund_refusal_explanation,
+        ]
+        .into_iter()
+        .flatten()
+        .cloned()
+        .collect::<Vec<_>>();
+        let documents = [
+            &evidence.receipt_provider_file_id,
+            &evidence.customer_communication_provider_file_id,
+            &evidence.shipping_documentation_provider_file_id,
+            &evidence.service_documentation_provider_file_id,
+            &evidence.invoice_showing_distinct_transactions_provider_file_id,
+            &evidence.recurring_transaction_agreement_provider_file_id,
+            &evidence.cancellation_policy_provider_file_id,
+            &evidence.refund_policy_provider_file_id,
+            &evidence.customer_signature_provider_file_id,
+            &evidence.uncategorized_file_provider_file_id,
+        ]
+        .into_iter()
+        .flatten()
+        .cloned()
+        .collect::<Vec<_>>();
+        // dLocal only takes a single defense per chargeback, an empty one would use it up
+        if comments.is_empty() && documents.is_empty() {
+            Err(errors::ConnectorError::MissingRequiredField {
+                field_name: "uncategorized_text",
+            })?
+        }
+        Ok(Self {
+            comments: (!comments.is_empty()).then(|| comments.join("\n")),
+            documents,
+        })
+    }
+}
+
+impl<F, T> TryFrom<ResponseRouterData<F, DlocalDisputeResponse, T, SubmitEvidenceResponse>>
+    for RouterData<F, T, SubmitEvidenceResponse>
+{
+    type Error = error_stack::Report<errors::ConnectorError>;
+    fn try_from(
+        item: ResponseRouterData<F, DlocalDisputeResponse, T, SubmitEvidenceResponse>,
+    ) -> Result<Self, Self::Error> {
         Ok(Self {
-            response: Ok(RefundsResponseData {
-                connector_refund_id: item.response.id,
-                refund_status,
+            response: Ok(SubmitEvidenceResponse {
+                // The chargeback stays open until the issuer reviews the defense
+                dispute_status: enums::DisputeStatus::DisputeChallenged,
+                connector_status: Some(item.response.status.to_string()),
             }),
             ..item.data
         })
     }
 }
 
+/// Error code returned by dLocal when a chargeback was already defended
+const CHARGEBACK_ALREADY_DEFENDED_ERROR_CODE: &str = "5014";
+
+/// dLocal accepts a single defense per chargeback, a second submission is reported with a
+/// message the merchant can act on rather than dLocal's generic validation error
+pub fn get_submit_evidence_error_response(error_response: ErrorResponse) -> ErrorResponse {
+    if error_response.code == CHARGEBACK_ALREADY_DEFENDED_ERROR_CODE {
+        ErrorResponse {
+            message: "Evidence was already submitted for this dispute, dLocal accepts a single \
+                defense per chargeback"
+                .to_string(),
+            reason: Some(error_response.message.clone()),
+            ..error_response
+        }
+    } else {
+        error_response
+    }
+}
+
+// WEBHOOKS :
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DlocalWebhookBody {
+    #[serde(rename = "id")]
+    pub payment_id: String,
+    pub status: DlocalPaymentStatus,
+    pub order_id: Option<String>,
+}
+
+impl From<&DlocalWebhookBody> for enums::AttemptStatus {
+    fn from(item: &DlocalWebhookBody) -> Self {
+        Self::from(item.status.clone())
+    }
+}
+
+impl From<&DlocalWebhookBody> for IncomingWebhookEvent {
+    fn from(item: &DlocalWebhookBody) -> Self {
+        match item.status {
+            DlocalPaymentStatus::Paid => Self::PaymentIntentSuccess,
+            DlocalPaymentStatus::Authorized | DlocalPaymentStatus::Verified => {
+                Self::PaymentIntentAuthorizationSuccess
+            }
+            DlocalPaymentStatus::Pending => Self::PaymentIntentProcessing,
+            DlocalPaymentStatus::Rejected | DlocalPaymentStatus::Expired => {
+                Self::PaymentIntentFailure
+            }
+            DlocalPaymentStatus::Cancelled => Self::PaymentIntentCancelled,
+            // Chargebacks are notified separately with the chargeback details
+            DlocalPaymentStatus::Chargeback => Self::EventNotSupported,
+        }
+    }
+}
+
+/// Refund notification, the body of a refund as returned by the refund sync along with the id
+/// of the refunded payment
+#[derive(Debug, Clone, Deserialize)]
+pub struct DlocalRefundWebhookBody {
+    pub payment_id: String,
+    #[serde(flatten)]
+    pub refund: RefundResponse,
+}
+
+/// Chargeback notification, the amount is in major units of the chargeback currency
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DlocalChargebackWebhookBody {
+    pub id: String,
+    pub payment_id: String,
+    pub amount: FloatMajorUnit,
+    pub currency: enums::Currency,
+    pub reason: Option<String>,
+    pub reason_code: String,
+    pub status: DlocalDisputeStatus,
+    pub created_date: Option<String>,
+}
+
+impl From<&DlocalChargebackWebhookBody> for IncomingWebhookEvent {
+    fn from(item: &DlocalChargebackWebhookBody) -> Self {
+        match item.status {
+            DlocalDisputeStatus::Pending => Self::DisputeOpened,
+            DlocalDisputeStatus::Dispute => Self::DisputeChallenged,
+            DlocalDisputeStatus::Accepted => Self::DisputeAccepted,
+            DlocalDisputeStatus::Won | DlocalDisputeStatus::Reversed => Self::DisputeWon,
+            DlocalDisputeStatus::Lost => Self::DisputeLost,
+            DlocalDisputeStatus::Expired => Self::DisputeExpired,
+            DlocalDisputeStatus::Cancelled => Self::DisputeCancelled,
+        }
+    }
+}
+
+impl TryFrom<&DlocalChargebackWebhookBody> for DisputePayload {
+    type Error = error_stack::Report<errors::ConnectorError>;
+    fn try_from(item: &DlocalChargebackWebhookBody) -> Result<Self, Self::Error> {
+        let amount = FloatMajorUnitForConnector
+            .convert_back(item.amount, item.currency)
+            .change_context(errors::ConnectorError::AmountConversionFailed)?;
+        Ok(Self {
+            amount: amount.get_amount_as_i64().to_string(),
+            currency: item.currency,
+            dispute_stage: enums::DisputeStage::Dispute,
+            connector_status: item.status.to_string(),
+            connector_dispute_id: item.id.clone(),
+            connector_reason: item.reason.clone(),
+            connector_reason_code: Some(item.reason_code.clone()),
+            challenge_required_by: None,
+            created_at: item.created_date.as_deref().and_then(parse_date_time),
+            updated_at: None,
+        })
+    }
+}
+
+/// dLocal doesn't send an event type with its notifications. Chargebacks are told apart by their
+/// `reason_code`, refunds by the `payment_id` of the refunded payment, payments only carry their
+/// own `id`
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum DlocalWebhookObject {
+    Chargeback(DlocalChargebackWebhookBody),
+    Refund(DlocalRefundWebhookBody),
+    Payment(DlocalWebhookBody),
+}
+
+impl From<&DlocalWebhookObject> for IncomingWebhookEvent {
+    fn from(item: &DlocalWebhookObject) -> Self {
+        match item {
+            DlocalWebhookObject::Chargeback(chargeback) => Self::from(chargeback),
+            DlocalWebhookObject::Payment(payment) => Self::from(payment),
+            DlocalWebhookObject::Refund(refund_webhook) => match refund_webhook.refund.status {
+                RefundStatus::Success => Self::RefundSuccess,
+                RefundStatus::Rejected => Self::RefundFailure,
+                // A cancelled refund is left for review, which the refund sync reports
+                RefundStatus::Cancelled | RefundStatus::Pending => Self::EventNotSupported,
+            },
+        }
+    }
+}
+
+/// dLocal signs notifications the same way requests are signed, the `Authorization` header
+/// carries `V2-HMAC-SHA256, Signature: <hex(HMAC-SHA256(secret, x_login + x_date + body))>`
+pub fn verify_webhook_signature(
+    auth: &DlocalAuthType,
+    x_date: &str,
+    authorization: &str,
+    body: &[u8],
+) -> CustomResult<bool, errors::ConnectorError> {
+    let signature = authorization
+        .split_once("Signature:")
+        .map(|(_, signature)| signature.trim())
+        .ok_or(errors::ConnectorError::WebhookSignatureNotFound)?;
+    let signature =
+        hex::decode(signature).change_context(errors::ConnectorError::WebhookSignatureNotFound)?;
+    let message = [auth.x_login.peek().as_bytes(), x_date.as_bytes(), body].concat();
+    crypto::HmacSha256
+        .verify_signature(auth.secret.peek().as_bytes(), &signature, &message)
+        .change_context(errors::ConnectorError::WebhookSourceVerificationFailed)
+}
+
 #[derive(Default, Debug, Serialize, Deserialize, PartialEq)]
 pub struct DlocalErrorResponse {
     pub code: i32,
     pub message: String,
     pub param: Option<String>,
+    pub retry_after: Option<u64>,
 }
 
-fn get_doc_from_currency(country: String) -> Secret<String> {
-    let doc = match country.as_str() {
-        "BR" => "91483309223",
-        "ZA" => "2001014800086",
-        "BD" | "GT" | "HN" | "PK" | "SN" | "TH" => "1234567890001",
-        "CR" | "SV" | "VN" => "123456789",
-        "DO" | "NG" => "12345678901",
-        "EG" => "12345678901112",
-        "GH" | "ID" | "RW" | "UG" => "1234567890111123",
-        "IN" => "NHSTP6374G",
-        "CI" => "CA124356789",
-        "JP" | "MY" | "PH" => "123456789012",
-        "NI" => "1234567890111A",
-        "TZ" => "12345678912345678900",
-        _ => "12345678",
-    };
-    Secret::new(doc.to_string())
+/// dLocal rate limits requests with a 429, the seconds to wait before retrying are taken from
+/// the `retry_after` of the body and otherwise from the `Retry-After` header
+pub fn get_rate_limit_error_response(
+    body: &[u8],
+    retry_after_header: Option<&str>,
+    http_code: u16,
+) -> ErrorResponse {
+    let response = serde_json::from_slice::<DlocalErrorResponse>(body).ok();
+    let retry_after = response
+        .as_ref()
+        .and_then(|response| response.retry_after)
+        .or_else(|| retry_after_header.and_then(|retry_after| retry_after.trim().parse().ok()));
+    ErrorResponse {
+        code: response
+            .as_ref()
+            .map(|response| response.code.to_string())
+            .unwrap_or(consts::NO_ERROR_CODE.to_string()),
+        message: response
+            .map(|response| response.message)
+            .unwrap_or(consts::NO_ERROR_MESSAGE.to_string()),
+        reason: Some(match retry_after {
+            Some(retry_after) => {
+                format!("Rate limited by dLocal, retry after {retry_after} seconds")
+            }
+            None => "Rate limited by dLocal".to_string(),
+        }),
+        status_code: http_code,
+        // The request was not processed, the attempt can be retried once the limit resets
+        attempt_status: None,
+        connector_transaction_id: None,
+    }
+}
+
+impl DlocalErrorResponse {
+    pub fn get_error_category(&self) -> Option<DlocalErrorCategory> {
+        DlocalErrorCategory::from_code(self.code)
+    }
+
+    /// dLocal's code and message are kept for the GSM lookup, known codes report their normalized
+    /// category and message as the reason while unknown codes are passed through as is
+    pub fn get_error_response(self, http_code: u16) -> ErrorResponse {
+        match self.get_error_category() {
+            Some(category) => ErrorResponse {
+                code: self.code.to_string(),
+                message: self.message,
+                reason: Some(format!(
+                    "{category}: {}",
+                    category.get_message(self.param.as_deref())
+                )),
+                status_code: http_code,
+                attempt_status: category.get_attempt_status(),
+                connector_transaction_id: None,
+            },
+            None => ErrorResponse {
+                code: self.code.to_string(),
+                message: self.message,
+                reason: self.param,
+                status_code: http_code,
+                // 3xx codes are card declines, retrying them will not change the outcome
+                attempt_status: (300..400)
+                    .contains(&self.code)
+                    .then_some(enums::AttemptStatus::Failure),
+                connector_transaction_id: None,
+            },
+        }
+    }
+}
+
+/// Normalized classification of dLocal's documented error and decline codes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, strum::Display, strum::EnumString)]
+#[strum(serialize_all = "SCREAMING_SNAKE_CASE")]
+pub enum DlocalErrorCategory {
+    InvalidCard,
+    InsufficientFunds,
+    IssuerUnavailable,
+    FraudDecline,
+    InvalidRequestField,
+}
+
+impl DlocalErrorCategory {
+    fn from_code(code: i32) -> Option<Self> {
+        match code {
+            // Card disabled, expired, requested or restricted by the bank, invalid number or
+            // security code
+            308 | 309 | 311 | 312 | 314 | 315 => Some(Self::InvalidCard),
+            302 => Some(Self::InsufficientFunds),
+            // The customer has to call the bank, or the request couldn't be processed
+            306 | 7000 => Some(Self::IssuerUnavailable),
+            // Blacklisted card, failed score validation, lost or stolen card or high risk user.
+            // 5014 is left out as dLocal also reports an already defended chargeback with it
+            303 | 304 | 310 | 313 | 317 => Some(Self::FraudDecline),
+            // Invalid request, parameter or request body, the parameter is named by `param`
+            5000..=5002 => Some(Self::InvalidRequestField),
+            _ => None,
+        }
+    }
+
+    fn get_message(self, param: Option<&str>) -> String {
+        match (self, param) {
+            (Self::InvalidCard, _) => "Invalid card".to_string(),
+            (Self::InsufficientFunds, _) => "Insufficient funds".to_string(),
+            (Self::IssuerUnavailable, _) => "Issuer unavailable".to_string(),
+            (Self::FraudDecline, _) => "Declined as fraudulent".to_string(),
+            (Self::InvalidRequestField, Some(param)) => format!("Invalid request field: {param}"),
+            (Self::InvalidRequestField, None) => "Invalid request field".to_string(),
+        }
+    }
+
+    /// Hard declines and invalid requests fail the attempt as retrying them will not change the
+    /// outcome, insufficient funds and unavailable issuers may succeed later
+    fn get_attempt_status(self) -> Option<enums::AttemptStatus> {
+        match self {
+            Self::InvalidCard | Self::FraudDecline | Self::InvalidRequestField => {
+                Some(enums::AttemptStatus::Failure)
+            }
+            Self::InsufficientFunds | Self::IssuerUnavailable => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use api_models::webhooks::IncomingWebhookEvent;
+    #[cfg(feature = "payouts")]
+    use common_enums::enums::PayoutStatus;
+    use common_enums::enums::{
+        AttemptStatus, AuthenticationType, CaptureMethod, CountryAlpha2, Currency, DisputeStatus,
+        FutureUsage, RefundStatus,
+    };
+    use common_utils::{
+        crypto::{HmacSha256, SignMessage},
+        request::Method,
+        types::{
+            AmountConvertor, FloatMajorUnit, FloatMajorUnitForConnector, MinorUnit, SemanticVersion,
+        },
+    };
+    use hyperswitch_domain_models::{
+        address::{AddressDetails, PhoneDetails},
+        router_data::{ErrorResponse, GooglePayDecryptedData, PaymentMethodToken},
+        router_request_types::{AuthenticationData, BrowserInformation},
+        router_response_types::RedirectForm,
+    };
+    use hyperswitch_interfaces::{
+        api::ConnectorCommon, disputes::DisputePayload, errors::ConnectorError, types::Response,
+    };
+    use masking::{ExposeInterface, PeekInterface, Secret};
+
+    use super::{
+        get_attempt_status, get_capture_status, get_card_holder_name,
+        get_connector_response_reference_id, get_dlocal_reference_id, get_expiration_time,
+        get_fraud_data, get_incremental_authorization_allowed, get_installments,
+        get_mandate_reference, get_network_txn_id, get_payer_address, get_payer_document,
+        get_payer_name, get_payer_phone, get_payment_method_flow, get_rate_limit_error_response,
+        get_redirection_data, get_rejected_payment_error_response, get_stored_card,
+        get_submit_evidence_error_response, get_three_dsecure, get_tokenized_card, get_wallet_card,
+        should_capture, should_save_card, validate_cancel_amount, validate_currency_for_country,
+        validate_document, validate_minimum_amount, validate_refund, validate_supported_country,
+        verify_webhook_signature, Card, DlocalAcceptDisputeResponse, DlocalAuthType,
+        DlocalBalanceResponse, DlocalChargebackWebhookBody, DlocalConnectorMetadataObject,
+        DlocalDisputeResponse, DlocalDisputeStatus, DlocalDocumentMode, DlocalErrorCategory,
+        DlocalErrorResponse, DlocalExternalThreeDSecure, DlocalIdempotencyKey,
+        DlocalPaymentMetadata, DlocalPaymentStatus, DlocalPaymentsCancelRequest,
+        DlocalPaymentsCancelResponse, DlocalPaymentsCaptureRequest, DlocalPaymentsCaptureResponse,
+        DlocalPaymentsRequest, DlocalPaymentsResponse, DlocalRefundPaymentMetadata,
+        DlocalRefundRequest, DlocalRefundRetry, DlocalWebhookBody, DlocalWebhookObject, Payer,
+        PaymentMethodFlow, PaymentMethodId, RefundResponse, ThreeDSecureReqData,
+        ThreeDSecureResData,
+    };
+    #[cfg(feature = "payouts")]
+    use super::{
+        DlocalPayoutBankAccount, DlocalPayoutBeneficiary, DlocalPayoutCreateRequest,
+        DlocalPayoutResponse,
+    };
+    use crate::{connectors::Dlocal, utils::MultipleCaptureSyncResponse};
+
+    fn to_major_unit(amount: i64, currency: Currency) -> FloatMajorUnit {
+        FloatMajorUnitForConnector
+            .convert(MinorUnit::new(amount), currency)
+            .unwrap_or_default()
+    }
+
+    fn to_utc_date_time(
+        year: i32,
+        month: time::Month,
+        day: u8,
+        (hour, minute, second): (u8, u8, u8),
+    ) -> Option<time::PrimitiveDateTime> {
+        time::Date::from_calendar_date(year, month, day)
+            .ok()
+            .zip(time::Time::from_hms(hour, minute, second).ok())
+            .map(|(date, time_of_day)| time::PrimitiveDateTime::new(date, time_of_day))
+    }
+
+    fn is_valid(country: CountryAlpha2, document: &str) -> bool {
+        validate_document(country, &Secret::new(document.to_string())).is_ok()
+    }
+
+    #[test]
+    fn test_validate_document_br() {
+        assert!(is_valid(CountryAlpha2::BR, "530.333.155-50"));
+        assert!(is_valid(CountryAlpha2::BR, "11.222.333/0001-81"));
+        assert!(!is_valid(CountryAlpha2::BR, "5303331555"));
+        assert!(!is_valid(CountryAlpha2::BR, "5303331555A"));
+    }
+
+    #[test]
+    fn test_validate_document_ar() {
+        assert!(is_valid(CountryAlpha2::AR, "12.345.678"));
+        assert!(is_valid(CountryAlpha2::AR, "20-12345678-6"));
+        assert!(!is_valid(CountryAlpha2::AR, "123456"));
+    }
+
+    #[test]
+    fn test_validate_document_cl() {
+        assert!(is_valid(CountryAlpha2::CL, "12.345.678-5"));
+        assert!(is_valid(CountryAlpha2::CL, "7654321-6"));
+        assert!(is_valid(CountryAlpha2::CL, "10000013-k"));
+        assert!(!is_valid(CountryAlpha2::CL, "12.345.678-9"));
+        assert!(!is_valid(CountryAlpha2::CL, "123-4"));
+    }
+
+    #[test]
+    fn test_validate_document_mx() {
+        assert!(is_valid(CountryAlpha2::MX, "GODE561231GR8"));
+        assert!(is_valid(CountryAlpha2::MX, "GODE561231HDFRRN09"));
+        assert!(!is_valid(CountryAlpha2::MX, "GODE5612"));
+    }
+
+    #[test]
+    fn test_validate_document_co() {
+        assert!(is_valid(CountryAlpha2::CO, "1.020.304.050"));
+        assert!(!is_valid(CountryAlpha2::CO, "12345"));
+        assert!(!is_valid(CountryAlpha2::CO, "10203040AB"));
+    }
+
+    #[test]
+    fn test_validate_document_in() {
+        assert!(is_valid(CountryAlpha2::IN, "ABCPE1234F"));
+        assert!(is_valid(CountryAlpha2::IN, "abcpe1234f"));
+        assert!(!is_valid(CountryAlpha2::IN, "ABCPE12345"));
+        assert!(!is_valid(CountryAlpha2::IN, "ABCP1234F"));
+        assert!(!is_valid(CountryAlpha2::IN, "1234567890"));
+    }
+
+    #[test]
+    fn test_missing_payer_document() {
+        let document = get_payer_document(Some(Secret::new("53033315550".to_string())));
+        assert_eq!(
+            document.ok().map(|document| document.expose()),
+            Some("53033315550".to_string())
+        );
+        assert!(get_payer_document(None).is_err_and(|error| matches!(
+            error.current_context(),
+            ConnectorError::MissingRequiredField {
+                field_name: "metadata.document"
+            }
+        )));
+    }
+
+    #[test]
+    fn test_payment_metadata_installments() {
+        for installments in [1, 6, 12] {
+            let metadata = Some(serde_json::json!({ "installments": installments }));
+            let payment_metadata = DlocalPaymentMetadata::try_from(&metadata);
+            assert_eq!(
+                payment_metadata
+                    .ok()
+                    .and_then(|metadata| metadata.installments),
+                Some(installments)
+            );
+        }
+        let payment_metadata = DlocalPaymentMetadata::try_from(&None);
+        assert_eq!(
+            payment_metadata
+                .ok()
+                .and_then(|metadata| metadata.installments),
+            None
+        );
+    }
+
+    #[test]
+    fn test_payment_metadata_installments_id() {
+        let metadata = Some(serde_json::json!({ "installments_id": "INS54434" }));
+        let payment_metadata = DlocalPaymentMetadata::try_from(&metadata);
+        assert_eq!(
+            payment_metadata
+                .ok()
+                .and_then(|metadata| metadata.installments_id),
+            Some("INS54434".to_string())
+        );
+    }
+
+    #[test]
+    fn test_installments_response_metadata() {
+        let response = r#"{
+            "id": "D-4-1",
+            "status": "PAID",
+            "installments": {
+                "id": "INS54434",
+                "installments": "3",
+                "installment_amount": 40.4,
+                "total_amount": 121.2
+            }
+        }"#;
+        let connector_metadata = serde_json::from_str::<DlocalPaymentsResponse>(response)
+            .ok()
+            .and_then(|response| response.get_connector_metadata().ok().flatten());
+        assert_eq!(
+            connector_metadata,
+            Some(serde_json::json!({
+                "installments": {
+                    "id": "INS54434",
+                    "installments": "3",
+                    "installment_amount": 40.4,
+                    "total_amount": 121.2
+                }
+            }))
+        );
+    }
+
+    #[test]
+    fn test_installments_range() {
+        assert_eq!(get_installments(None).ok(), Some(None));
+        assert_eq!(get_installments(Some(1)).ok(), Some(Some("1".to_string())));
+        assert_eq!(
+            get_installments(Some(36)).ok(),
+            Some(Some("36".to_string()))
+        );
+        assert!(get_installments(Some(0)).is_err());
+        assert!(get_installments(Some(37)).is_err());
+    }
+
+    #[test]
+    fn test_validate_document_other_countries_are_not_checked() {
+        assert!(is_valid(CountryAlpha2::US, "anything"));
+    }
+
+    #[test]
+    fn test_sync_response_with_pending_3ds_redirect() {
+        // PSync parses the payment response, an abandoned 3DS session still has the redirect url
+        let response = serde_json::from_str::<DlocalPaymentsResponse>(
+            r#"{
+                "id": "D-4-e2227981-8ec8-48fd-8e9a-19fedb08d73a",
+                "status": "PENDING",
+                "order_id": "pay_12345",
+                "three_dsecure": {
+                    "redirect_url": "https://sandbox.dlocal.com/collect/3ds/D-4-e2227981"
+                }
+            }"#,
+        );
+        let redirect_url = response.as_ref().ok().and_then(|response| {
+            response
+                .three_dsecure
+                .as_ref()
+                .and_then(|three_dsecure| three_dsecure.redirect_url.as_ref())
+                .map(|redirect_url| redirect_url.to_string())
+        });
+        assert_eq!(
+            response.map(|response| response.status).ok(),
+            Some(DlocalPaymentStatus::Pending)
+        );
+        assert_eq!(
+            redirect_url.as_deref(),
+            Some("https://sandbox.dlocal.com/collect/3ds/D-4-e2227981")
+        );
+    }
+
+    #[test]
+    fn test_mandate_reference_from_saved_card() {
+        let connector_mandate_id = |response: &str, setup_future_usage| {
+            serde_json::from_str::<DlocalPaymentsResponse>(response)
+                .ok()
+                .and_then(|response| get_mandate_reference(response.card, setup_future_usage))
+                .and_then(|mandate_reference| mandate_reference.connector_mandate_id)
+        };
+        let response_with_card = r#"{
+            "id": "D-4-cf8f4a5a-8d2c-4cb5-b4f0-3cc1e4a2b8a1",
+            "status": "PAID",
+            "card": { "card_id": "CV-ecd897ac-5361-45c1-a407-aaab044ce87e" }
+        }"#;
+        let response_without_card = r#"{
+            "id": "D-4-cf8f4a5a-8d2c-4cb5-b4f0-3cc1e4a2b8a1",
+            "status": "PAID"
+        }"#;
+        assert_eq!(
+            connector_mandate_id(response_with_card, Some(FutureUsage::OffSession)).as_deref(),
+            Some("CV-ecd897ac-5361-45c1-a407-aaab044ce87e")
+        );
+        assert_eq!(
+            connector_mandate_id(response_with_card, Some(FutureUsage::OnSession)),
+            None
+        );
+        assert_eq!(
+            connector_mandate_id(response_without_card, Some(FutureUsage::OffSession)),
+            None
+        );
+    }
+
+    #[test]
+    fn test_rejected_response_error() {
+        let response = serde_json::from_str::<DlocalPaymentsResponse>(
+            r#"{
+                "id": "D-4-9a3e5f5b-1c3a-4e0f-8f6a-0b6f8d5c2e11",
+                "status": "REJECTED",
+                "status_code": "300",
+                "status_detail": "The payment was rejected."
+            }"#,
+        );
+        let error_response = response
+            .ok()
+            .and_then(|response| response.get_error_response(200));
+        assert_eq!(
+            error_response
+                .as_ref()
+                .map(|error_response| error_response.code.as_str()),
+            Some("300")
+        );
+        assert_eq!(
+            error_response
+                .as_ref()
+                .and_then(|error_response| error_response.reason.as_deref()),
+            Some("The payment was rejected.")
+        );
+        assert_eq!(
+            error_response.map(|error_response| error_response.message),
+            Some("The payment was rejected.".to_string())
+        );
+    }
+
+    #[test]
+    fn test_payment_status_mapping() {
+        assert_eq!(
+            AttemptStatus::from(DlocalPaymentStatus::Paid),
+            AttemptStatus::Charged
+        );
+        assert_eq!(
+            AttemptStatus::from(DlocalPaymentStatus::Authorized),
+            AttemptStatus::Authorized
+        );
+        assert_eq!(
+            AttemptStatus::from(DlocalPaymentStatus::Pending),
+            AttemptStatus::Pending
+        );
+        assert_eq!(
+            AttemptStatus::from(DlocalPaymentStatus::Rejected),
+            AttemptStatus::Failure
+        );
+    }
+
+    #[test]
+    fn test_expired_and_chargeback_statuses() {
+        let status = |response: &str| {
+            serde_json::from_str::<DlocalPaymentsResponse>(response)
+                .ok()
+                .map(|response| response.status)
+        };
+        assert_eq!(
+            status(r#"{ "id": "D-4-1", "status": "EXPIRED" }"#),
+            Some(DlocalPaymentStatus::Expired)
+        );
+        assert_eq!(
+            status(r#"{ "id": "D-4-1", "status": "CHARGEBACK" }"#),
+            Some(DlocalPaymentStatus::Chargeback)
+        );
+        assert_eq!(
+            AttemptStatus::from(DlocalPaymentStatus::Expired),
+            AttemptStatus::Failure
+        );
+        assert_eq!(
+            AttemptStatus::from(DlocalPaymentStatus::Chargeback),
+            AttemptStatus::Charged
+        );
+    }
+
+    #[test]
+    fn test_dispute_response() {
+        let response = r#"{
+            "id": "CHB-4-1",
+            "payment_id": "D-4-1",
+            "amount": 100.5,
+            "currency": "BRL",
+            "reason": "Fraudulent transaction",
+            "status": "PENDING",
+            "status_code": "100",
+            "status_detail": "The chargeback is pending.",
+            "created_date": "2024-05-21T13:36:52.000+0000"
+        }"#;
+        let dispute = serde_json::from_str::<DlocalDisputeResponse>(response).ok();
+        assert_eq!(
+            dispute.as_ref().map(|dispute| dispute.payment_id.as_str()),
+            Some("D-4-1")
+        );
+        assert_eq!(
+            dispute.as_ref().map(|dispute| dispute.currency),
+            Some(Currency::BRL)
+        );
+        assert_eq!(
+            dispute.map(|dispute| DisputeStatus::from(dispute.status)),
+            Some(DisputeStatus::DisputeOpened)
+        );
+        assert_eq!(
+            DisputeStatus::from(DlocalDisputeStatus::Reversed),
+            DisputeStatus::DisputeWon
+        );
+        assert_eq!(DlocalDisputeStatus::Lost.to_string(), "LOST");
+    }
+
+    #[test]
+    fn test_capture_sync_response() {
+        let response = r#"{
+            "id": "D-4-2",
+            "status": "PAID",
+            "amount": 10.5,
+            "currency": "BRL",
+            "order_id": "order_1"
+        }"#;
+        let capture = serde_json::from_str::<DlocalPaymentsResponse>(response).ok();
+        assert_eq!(
+            capture
+                .as_ref()
+                .and_then(|capture| capture.get_amount_captured().ok()),
+            Some(Some(MinorUnit::new(1050)))
+        );
+        assert_eq!(
+            capture.map(|capture| capture.get_capture_attempt_status()),
+            Some(AttemptStatus::Charged)
+        );
+        assert_eq!(
+            get_capture_status(&DlocalPaymentStatus::Pending),
+            AttemptStatus::Pending
+        );
+        assert_eq!(
+            get_capture_status(&DlocalPaymentStatus::Rejected),
+            AttemptStatus::CaptureFailed
+        );
+    }
+
+    #[test]
+    fn test_idempotency_key_is_deterministic() {
+        let key = |reference_id| {
+            DlocalIdempotencyKey::from_reference_id(reference_id)
+                .ok()
+                .map(|key| key.get_key())
+        };
+        let first_attempt = key("pay_HfVQ1R7iNvQv1wKLyYdR_1");
+        assert!(first_attempt.is_some());
+        assert_eq!(first_attempt, key("pay_HfVQ1R7iNvQv1wKLyYdR_1"));
+        assert_ne!(first_attempt, key("pay_HfVQ1R7iNvQv1wKLyYdR_2"));
+    }
+
+    #[test]
+    fn test_tokenized_card_omits_card_details() {
+        let card = get_tokenized_card(
+            Secret::new("John Doe".to_string()),
+            Secret::new("CV-5b1b7b93-7a4b-4c52-a4b4-2f9d01ab1a5c".to_string()),
+            true,
+        );
+        let card = serde_json::to_value(card).ok();
+        assert_eq!(
+            card,
+            Some(serde_json::json!({
+                "holder_name": "John Doe",
+                "token": "CV-5b1b7b93-7a4b-4c52-a4b4-2f9d01ab1a5c",
+                "capture": true
+            }))
+        );
+    }
+
+    #[cfg(feature = "payouts")]
+    #[test]
+    fn test_payout_sync_response() {
+        let payout = |response: &str| {
+            serde_json::from_str::<DlocalPayoutResponse>(response)
+                .ok()
+                .map(|response| response.get_payout_response(PayoutStatus::Pending))
+        };
+
+        let received = payout(r#"{ "id": "PO-1", "status": "RECEIVED" }"#);
+        assert_eq!(
+            received.as_ref().and_then(|payout| payout.status),
+            Some(PayoutStatus::Pending)
+        );
+        assert_eq!(
+            received.and_then(|payout| payout.connector_payout_id),
+            Some("PO-1".to_string())
+        );
+
+        let completed = payout(r#"{ "id": "PO-1", "status": "COMPLETED" }"#);
+        assert_eq!(
+            completed.and_then(|payout| payout.status),
+            Some(PayoutStatus::Success)
+        );
+
+        let delivered = payout(r#"{ "id": "PO-1", "status": "DELIVERED" }"#);
+        assert_eq!(
+            delivered.and_then(|payout| payout.status),
+            Some(PayoutStatus::Success)
+        );
+
+        let rejected = payout(
+            r#"{
+                "id": "PO-1",
+                "status": "REJECTED",
+                "status_code": "302",
+                "status_detail": "Invalid beneficiary account"
+            }"#,
+        );
+        assert_eq!(
+            rejected.as_ref().and_then(|payout| payout.status),
+            Some(PayoutStatus::Failed)
+        );
+        assert_eq!(
+            rejected
+                .as_ref()
+                .and_then(|payout| payout.error_code.clone()),
+            Some("302".to_string())
+        );
+        assert_eq!(
+            rejected.and_then(|payout| payout.error_message),
+            Some("Invalid beneficiary account".to_string())
+        );
+    }
+
+    #[test]
+    fn test_stored_card_for_merchant_initiated_payment() {
+        let card = get_stored_card(
+            Secret::new("John Doe".to_string()),
+            "CID-124c18a5-874d-4982-89d7-b9c256e647b5".to_string(),
+            false,
+        );
+        assert_eq!(
+            serde_json::to_value(card).ok(),
+            Some(serde_json::json!({
+                "holder_name": "John Doe",
+                "card_id": "CID-124c18a5-874d-4982-89d7-b9c256e647b5",
+                "stored_credential_type": "UNSCHEDULED_CARD_ON_FILE",
+                "stored_credential_usage": "USED",
+                "capture": false
+            }))
+        );
+    }
+
+    #[test]
+    fn test_webhook_event_type() {
+        let event = |body: &str| {
+            serde_json::from_str::<DlocalWebhookBody>(body)
+                .ok()
+                .map(|body| IncomingWebhookEvent::from(&body))
+        };
+        assert_eq!(
+            event(r#"{ "id": "D-4-1", "status": "PAID", "order_id": "order_1" }"#),
+            Some(IncomingWebhookEvent::PaymentIntentSuccess)
+        );
+        assert_eq!(
+            event(r#"{ "id": "D-4-1", "status": "PENDING" }"#),
+            Some(IncomingWebhookEvent::PaymentIntentProcessing)
+        );
+        assert_eq!(
+            event(r#"{ "id": "D-4-1", "status": "EXPIRED" }"#),
+            Some(IncomingWebhookEvent::PaymentIntentFailure)
+        );
+    }
+
+    #[test]
+    fn test_card_connector_metadata() {
+        let response = r#"{
+            "id": "D-4-2",
+            "status": "PAID",
+            "card": {
+                "holder_name": "Thomas",
+                "expiration_month": 10,
+                "expiration_year": 2040,
+                "brand": "VI",
+                "last4": "1111",
+                "card_id": "CV-124c18a5-874d-4982-89d7-b9c256e647b5"
+            },
+            "three_dsecure": {
+                "eci": "05",
+                "three_dsecure_version": "2.2.0"
+            }
+        }"#;
+        let connector_metadata = serde_json::from_str::<DlocalPaymentsResponse>(response)
+            .ok()
+            .and_then(|response| response.get_connector_metadata().ok().flatten());
+        assert_eq!(
+            connector_metadata,
+            Some(serde_json::json!({
+                "card": {
+                    "brand": "VI",
+                    "last4": "1111"
+                },
+                "three_dsecure": {
+                    "eci": "05",
+                    "three_dsecure_version": "2.2.0"
+                }
+            }))
+        );
+        let serialized = connector_metadata
+            .map(|metadata| metadata.to_string())
+            .unwrap_or_default();
+        assert!(serialized.contains("VI"));
+        assert!(!serialized.contains("4111111111111111"));
+        assert!(!serialized.contains("Thomas"));
+        assert!(!serialized.contains("CV-124c18a5"));
+    }
+
+    #[test]
+    fn test_expiration_time() {
+        let serialize = |expiration_time| {
+            serde_json::to_value(DlocalPaymentsRequest {
+                expiration_time,
+                ..Default::default()
+            })
+            .ok()
+        };
+        let with_expiration = serialize(get_expiration_time(Some(30), false));
+        assert!(with_expiration
+            .as_ref()
+            .and_then(|request| request.get("expiration_time"))
+            .and_then(|expiration_time| expiration_time.as_str())
+            .is_some_and(|expiration_time| expiration_time.ends_with('Z')));
+
+        assert_eq!(get_expiration_time(None, false), None);
+        let without_expiration = serialize(None);
+        assert!(without_expiration.is_some());
+        assert!(without_expiration
+            .as_ref()
+            .and_then(|request| request.get("expiration_time"))
+            .is_none());
+
+        // Redirect payments fall back to the default expiry
+        assert!(get_expiration_time(None, true).is_some());
+    }
+
+    #[test]
+    fn test_verify_webhook_signature() {
+        let auth = DlocalAuthType {
+            x_login: Secret::new("sak223k2wdksdl2".to_string()),
+            x_trans_key: Secret::new("fm12O7G9".to_string()),
+            secret: Secret::new("dlocal_secret".to_string()),
+        };
+        let x_date = "2024-05-21T23:59:59.000Z";
+        let body = br#"{"id":"D-4-1","status":"PAID","order_id":"pay_1"}"#;
+        let message = [b"sak223k2wdksdl2".as_slice(), x_date.as_bytes(), body].concat();
+        let authorization = HmacSha256
+            .sign_message(b"dlocal_secret", &message)
+            .map(|signature| format!("V2-HMAC-SHA256, Signature: {}", hex::encode(signature)))
+            .unwrap_or_default();
+
+        assert_eq!(
+            verify_webhook_signature(&auth, x_date, &authorization, body).ok(),
+            Some(true)
+        );
+
+        let tampered_body = br#"{"id":"D-4-1","status":"PAID","order_id":"pay_2"}"#;
+        assert_eq!(
+            verify_webhook_signature(&auth, x_date, &authorization, tampered_body).ok(),
+            Some(false)
+        );
+
+        let missing_signature = verify_webhook_signature(&auth, x_date, "", body);
+        assert!(missing_signature.is_err_and(|error| matches!(
+            error.current_context(),
+            ConnectorError::WebhookSignatureNotFound
+        )));
+    }
+
+    #[test]
+    fn test_validate_minimum_amount() {
+        assert!(validate_minimum_amount(CountryAlpha2::BR, Currency::BRL, 99).is_err());
+        assert!(validate_minimum_amount(CountryAlpha2::BR, Currency::BRL, 100).is_ok());
+        assert!(validate_minimum_amount(CountryAlpha2::MX, Currency::MXN, 999).is_err());
+        assert!(validate_minimum_amount(CountryAlpha2::MX, Currency::MXN, 1000).is_ok());
+        // Only payments in the local currency have a known minimum
+        assert!(validate_minimum_amount(CountryAlpha2::BR, Currency::USD, 1).is_ok());
+
+        let below_minimum = validate_minimum_amount(CountryAlpha2::MX, Currency::MXN, 500);
+        assert!(below_minimum.is_err_and(|error| matches!(
+            error.current_context(),
+            ConnectorError::RequestEncodingFailedWithReason(reason) if reason.contains("10.00 MXN")
+        )));
+    }
+
+    #[test]
+    fn test_refund_webhook() {
+        let event = |body: &str| {
+            serde_json::from_str::<DlocalWebhookObject>(body)
+                .ok()
+                .map(|webhook_object| IncomingWebhookEvent::from(&webhook_object))
+        };
+        let refund_body = r#"{
+            "id": "REF-15104-a9cc29ef-1041-4d5f-8e4d-2d1e6b7e3f25",
+            "payment_id": "D-15104-9f5246d5-34e2-4f63-9d29-380ab1567ec8",
+            "status": "SUCCESS",
+            "currency": "BRL",
+            "amount": 100
+        }"#;
+        let refund_id = serde_json::from_str::<DlocalWebhookObject>(refund_body)
+            .ok()
+            .and_then(|webhook_object| match webhook_object {
+                DlocalWebhookObject::Refund(webhook_body) => Some(webhook_body.refund.id),
+                DlocalWebhookObject::Payment(_) | DlocalWebhookObject::Chargeback(_) => None,
+            });
+        assert_eq!(
+            refund_id,
+            Some("REF-15104-a9cc29ef-1041-4d5f-8e4d-2d1e6b7e3f25".to_string())
+        );
+        assert_eq!(
+            event(refund_body),
+            Some(IncomingWebhookEvent::RefundSuccess)
+        );
+        assert_eq!(
+            event(r#"{ "id": "REF-1", "payment_id": "D-4-1", "status": "REJECTED" }"#),
+            Some(IncomingWebhookEvent::RefundFailure)
+        );
+        assert_eq!(
+            event(r#"{ "id": "REF-1", "payment_id": "D-4-1", "status": "CANCELLED" }"#),
+            Some(IncomingWebhookEvent::EventNotSupported)
+        );
+        // A payment notification has no `payment_id`
+        assert_eq!(
+            event(r#"{ "id": "D-4-1", "status": "PAID" }"#),
+            Some(IncomingWebhookEvent::PaymentIntentSuccess)
+        );
+    }
+
+    #[test]
+    fn test_redacted_request() {
+        let request = DlocalPaymentsRequest {
+            amount: to_major_unit(1500, Currency::BRL),
+            currency: Currency::BRL,
+            order_id: "pay_Uj7fH3UtlHCFhLCjWNBr_1".to_string(),
+            payer: Payer {
+                document: Secret::new("53033315550".to_string()),
+                ..Default::default()
+            },
+            card: Some(Card {
+                number: "4111111111111111"
+                    .parse::<cards::CardNumber>()
+                    .ok()
+                    .map(Into::into),
+                cvv: Some(Secret::new("737".to_string())),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let redacted = request
+            .to_redacted_json()
+            .map(|request| request.to_string())
+            .unwrap_or_default();
+        assert!(redacted.contains(r#""amount":15.0"#));
+        assert!(redacted.contains("BRL"));
+        assert!(redacted.contains("pay_Uj7fH3UtlHCFhLCjWNBr_1"));
+        assert!(!redacted.contains("4111111111111111"));
+        assert!(!redacted.contains("737"));
+        assert!(!redacted.contains("53033315550"));
+    }
+
+    #[test]
+    fn test_chargeback_webhook() {
+        let chargeback_body = r#"{
+            "id": "CHAR42-2024-05-21-1",
+            "payment_id": "D-4-1",
+            "amount": 100.5,
+            "currency": "BRL",
+            "reason": "Fraudulent transaction",
+            "reason_code": "4837",
+            "status": "PENDING",
+            "created_date": "2024-05-21T13:36:52.000+0000"
+        }"#;
+        let webhook_object = serde_json::from_str::<DlocalWebhookObject>(chargeback_body).ok();
+        assert_eq!(
+            webhook_object.as_ref().map(IncomingWebhookEvent::from),
+            Some(IncomingWebhookEvent::DisputeOpened)
+        );
+        let dispute = serde_json::from_str::<DlocalChargebackWebhookBody>(chargeback_body)
+            .ok()
+            .and_then(|chargeback| DisputePayload::try_from(&chargeback).ok());
+        // The chargeback amount is in major units, disputes are stored in minor units
+        assert_eq!(
+            dispute.as_ref().map(|dispute| dispute.amount.as_str()),
+            Some("10050")
+        );
+        assert_eq!(
+            dispute.as_ref().map(|dispute| dispute.currency),
+            Some(Currency::BRL)
+        );
+        assert_eq!(
+            dispute
+                .as_ref()
+                .map(|dispute| dispute.connector_dispute_id.as_str()),
+            Some("CHAR42-2024-05-21-1")
+        );
+        assert_eq!(
+            dispute
+                .as_ref()
+                .and_then(|dispute| dispute.connector_reason_code.as_deref()),
+            Some("4837")
+        );
+        assert!(dispute.is_some_and(|dispute| dispute.created_at.is_some()));
+
+        let won = r#"{
+            "id": "CHAR42-2024-05-21-1",
+            "payment_id": "D-4-1",
+            "amount": 100.5,
+            "currency": "BRL",
+            "reason_code": "4837",
+            "status": "WON"
+        }"#;
+        assert_eq!(
+            serde_json::from_str::<DlocalWebhookObject>(won)
+                .ok()
+                .map(|webhook_object| IncomingWebhookEvent::from(&webhook_object)),
+            Some(IncomingWebhookEvent::DisputeWon)
+        );
+
+        // A refund has no `reason_code`
+        let refund = r#"{ "id": "REF-1", "payment_id": "D-4-1", "status": "CANCELLED" }"#;
+        assert!(matches!(
+            serde_json::from_str::<DlocalWebhookObject>(refund),
+            Ok(DlocalWebhookObject::Refund(_))
+        ));
+    }
+
+    #[test]
+    fn test_submit_evidence_already_defended() {
+        let error_response = |code: &str| ErrorResponse {
+            code: code.to_string(),
+            message: "Invalid request".to_string(),
+            reason: None,
+            status_code: 400,
+            attempt_status: None,
+            connector_transaction_id: None,
+        };
+        let already_defended = get_submit_evidence_error_response(error_response("5014"));
+        assert_eq!(already_defended.code, "5014");
+        assert!(already_defended.message.contains("already submitted"));
+        assert_eq!(already_defended.reason.as_deref(), Some("Invalid request"));
+
+        let other_error = get_submit_evidence_error_response(error_response("5000"));
+        assert_eq!(other_error.message, "Invalid request");
+        assert_eq!(other_error.reason, None);
+
+        // The code has to reach the dispute flow unchanged by the error code mapping
+        let already_defended = Dlocal::new()
+            .build_error_response(
+                Response {
+                    headers: None,
+                    response: r#"{"code":5014,"message":"Chargeback already defended"}"#.into(),
+                    status_code: 400,
+                },
+                None,
+            )
+            .map(get_submit_evidence_error_response)
+            .ok();
+        assert!(already_defended.is_some_and(|error_response| {
+            error_response.code == "5014"
+                && error_response.message.contains("already submitted")
+                && error_response.reason.as_deref() == Some("Chargeback already defended")
+                && error_response.attempt_status.is_none()
+        }));
+    }
+
+    #[test]
+    fn test_boleto_request_and_barcode_response() {
+        let request = serde_json::to_value(DlocalPaymentsRequest {
+            amount: to_major_unit(10000, Currency::BRL),
+            currency: Currency::BRL,
+            country: "BR".to_string(),
+            payment_method_id: PaymentMethodId::Boleto,
+            payment_method_flow: PaymentMethodFlow::Direct,
+            ..Default::default()
+        })
+        .ok();
+        assert_eq!(
+            request
+                .as_ref()
+                .and_then(|request| request.get("payment_method_id")),
+            Some(&serde_json::json!("BL"))
+        );
+        // The DIRECT flow returns the barcode, REDIRECT would only return dLocal's hosted page
+        assert_eq!(
+            request
+                .as_ref()
+                .and_then(|request| request.get("payment_method_flow")),
+            Some(&serde_json::json!("DIRECT"))
+        );
+
+        let response = r#"{
+            "id": "D-4-3",
+            "status": "PENDING",
+            "ticket": {
+                "number": "23790001246000002889380000100005",
+                "barcode": "23791955500000100003380260000288938000010000",
+                "expiration_date": "2024-05-21T23:59:59.000+0000",
+                "image_url": "https://sandbox.dlocal.com/gmf-apm/payments/D-4-3/ticket.png",
+                "url": "https://sandbox.dlocal.com/gmf-apm/payments/D-4-3"
+            }
+        }"#;
+        let connector_metadata = serde_json::from_str::<DlocalPaymentsResponse>(response)
+            .ok()
+            .and_then(|response| response.get_connector_metadata().ok().flatten());
+        assert_eq!(
+            connector_metadata
+                .as_ref()
+                .and_then(|metadata| metadata.get("reference")),
+            Some(&serde_json::json!(
+                "23791955500000100003380260000288938000010000"
+            ))
+        );
+        assert!(connector_metadata
+            .as_ref()
+            .and_then(|metadata| metadata.get("expires_at"))
+            .is_some_and(|expires_at| expires_at.is_i64()));
+    }
+
+    #[test]
+    fn test_accept_dispute_response_shapes() {
+        let chargeback = r#"{
+            "id": "CHAR42-2024-05-21-1",
+            "payment_id": "D-4-1",
+            "amount": 100.5,
+            "currency": "BRL",
+            "status": "ACCEPTED",
+            "status_code": "400",
+            "status_detail": "The chargeback was accepted by the merchant."
+        }"#;
+        let response = serde_json::from_str::<DlocalAcceptDisputeResponse>(chargeback).ok();
+        assert!(matches!(
+            response,
+            Some(DlocalAcceptDisputeResponse::Chargeback(_))
+        ));
+        assert_eq!(
+            response.map(|response| response.get_dispute_status()),
+            Some(DisputeStatus::DisputeAccepted)
+        );
+
+        let acknowledgment = r#"{ "id": "CHAR42-2024-05-21-1", "status": "ACCEPTED" }"#;
+        let response = serde_json::from_str::<DlocalAcceptDisputeResponse>(acknowledgment).ok();
+        assert!(matches!(
+            response,
+            Some(DlocalAcceptDisputeResponse::Acknowledgment(_))
+        ));
+        assert_eq!(
+            response.and_then(|response| response.get_connector_status()),
+            Some(DlocalDisputeStatus::Accepted)
+        );
+
+        let empty_acknowledgment = serde_json::from_str::<DlocalAcceptDisputeResponse>("{}").ok();
+        assert_eq!(
+            empty_acknowledgment.map(|response| response.get_dispute_status()),
+            Some(DisputeStatus::DisputeAccepted)
+        );
+    }
+
+    #[test]
+    fn test_pending_sub_status() {
+        let status = |status_code: Option<&str>, is_awaiting_customer| {
+            get_attempt_status(
+                DlocalPaymentStatus::Pending,
+                status_code,
+                is_awaiting_customer,
+            )
+        };
+        // awaiting the 3DS challenge, redirect or voucher to be completed
+        assert_eq!(
+            status(Some("100"), true),
+            AttemptStatus::AuthenticationPending
+        );
+        assert_eq!(status(None, true), AttemptStatus::AuthenticationPending);
+        // processing with nothing left for the customer to do
+        assert_eq!(status(Some("100"), false), AttemptStatus::Pending);
+        assert_eq!(status(None, false), AttemptStatus::Pending);
+        // held for a manual review
+        assert_eq!(status(Some("104"), true), AttemptStatus::Pending);
+        // the sub-status is only meaningful for pending payments
+        assert_eq!(
+            get_attempt_status(DlocalPaymentStatus::Paid, Some("104"), true),
+            AttemptStatus::Charged
+        );
+    }
+
+    #[test]
+    fn test_pending_status_of_response_shapes() {
+        let sync_status = |response: &str| {
+            serde_json::from_str::<DlocalPaymentsResponse>(response)
+                .ok()
+                .map(|response| {
+                    get_attempt_status(
+                        response.status.clone(),
+                        response.status_code.as_deref(),
+                        response.is_awaiting_customer(),
+                    )
+                })
+        };
+        let three_ds_pending = r#"{
+            "id": "D-4-1",
+            "status": "PENDING",
+            "status_code": "100",
+            "three_dsecure": {
+                "redirect_url": "https://sandbox.dlocal.com/collect/3ds/D-4-1"
+            }
+        }"#;
+        assert_eq!(
+            sync_status(three_ds_pending),
+            Some(AttemptStatus::AuthenticationPending)
+        );
+        let voucher_pending = r#"{
+            "id": "D-4-2",
+            "status": "PENDING",
+            "status_code": "100",
+            "ticket": {
+                "number": "23790001246000002889380000100005"
+            }
+        }"#;
+        assert_eq!(
+            sync_status(voucher_pending),
+            Some(AttemptStatus::AuthenticationPending)
+        );
+        let card_pending = r#"{"id":"D-4-3","status":"PENDING","status_code":"100"}"#;
+        assert_eq!(sync_status(card_pending), Some(AttemptStatus::Pending));
+        let under_review = r#"{
+            "id": "D-4-4",
+            "status": "PENDING",
+            "status_code": "104",
+            "status_detail": "The payment is under review."
+        }"#;
+        assert_eq!(sync_status(under_review), Some(AttemptStatus::Pending));
+
+        let payment_status = |response: &str| {
+            serde_json::from_str::<DlocalPaymentsResponse>(response)
+                .ok()
+                .map(|response| {
+                    get_attempt_status(
+                        response.status.clone(),
+                        response.status_code.as_deref(),
+                        response.is_awaiting_customer(),
+                    )
+                })
+        };
+        let pix_pending = r#"{
+            "id": "D-4-5",
+            "status": "PENDING",
+            "redirect_url": "https://sandbox.dlocal.com/collect/pix/D-4-5"
+        }"#;
+        assert_eq!(
+            payment_status(pix_pending),
+            Some(AttemptStatus::AuthenticationPending)
+        );
+        assert_eq!(payment_status(card_pending), Some(AttemptStatus::Pending));
+    }
+
+    #[test]
+    fn test_validate_refund() {
+        assert!(validate_refund(1000, Currency::BRL, Some(Currency::BRL)).is_ok());
+        // payments made before the currency was kept in the metadata
+        assert!(validate_refund(1000, Currency::BRL, None).is_ok());
+
+        let currency_mismatch = validate_refund(1000, Currency::USD, Some(Currency::BRL));
+        assert!(currency_mismatch.is_err_and(|error| matches!(
+            error.current_context(),
+            ConnectorError::RequestEncodingFailedWithReason(reason)
+                if reason == "refund currency USD does not match the payment currency BRL"
+        )));
+        let zero_amount = validate_refund(0, Currency::BRL, Some(Currency::BRL));
+        assert!(zero_amount.is_err_and(|error| matches!(
+            error.current_context(),
+            ConnectorError::RequestEncodingFailedWithReason(reason)
+                if reason == "refund amount must be greater than zero"
+        )));
+
+        let response = r#"{"id":"D-4-4","status":"PAID","amount":10.0,"currency":"BRL"}"#;
+        let payment_currency = serde_json::from_str::<DlocalPaymentsResponse>(response)
+            .ok()
+            .and_then(|response| response.get_connector_metadata().ok().flatten())
+            .and_then(|metadata| {
+                serde_json::from_value::<DlocalRefundPaymentMetadata>(metadata).ok()
+            })
+            .and_then(|metadata| metadata.currency);
+        assert_eq!(payment_currency, Some(Currency::BRL));
+    }
+
+    #[test]
+    fn test_partial_refund_response() {
+        let response = r#"{
+            "id": "REF-15104-a9cc29ef-1041-4d5f-8e4d-2d1e6b7e3f25",
+            "payment_id": "D-15104-9f5246d5-34e2-4f63-9d29-380ab1567ec8",
+            "status": "SUCCESS",
+            "currency": "BRL",
+            "amount": 25.5,
+            "created_date": "2024-05-21T12:30:00.000+0000"
+        }"#;
+        let integrity_object = serde_json::from_str::<RefundResponse>(response)
+            .ok()
+            .and_then(|response| response.get_integrity_object().ok().flatten());
+        assert_eq!(
+            integrity_object
+                .as_ref()
+                .map(|integrity_object| integrity_object.refund_amount),
+            Some(MinorUnit::new(2550))
+        );
+        assert_eq!(
+            integrity_object.map(|integrity_object| integrity_object.currency),
+            Some(Currency::BRL)
+        );
+
+        // Responses without the amount leave the refund as requested
+        let response = r#"{"id":"REF-15104-1","status":"PENDING"}"#;
+        let integrity_object = serde_json::from_str::<RefundResponse>(response)
+            .ok()
+            .and_then(|response| response.get_integrity_object().ok().flatten());
+        assert!(integrity_object.is_none());
+    }
+
+    #[test]
+    fn test_amounts_in_major_units() {
+        // Zero decimal currencies are sent as is, others are scaled down by their number of
+        // decimal places
+        for (minor_amount, currency, major_amount) in [
+            (1050, Currency::JPY, serde_json::json!(1050.0)),
+            (1050, Currency::CLP, serde_json::json!(1050.0)),
+            (1050, Currency::BRL, serde_json::json!(10.5)),
+            (1050, Currency::USD, serde_json::json!(10.5)),
+            (1050, Currency::BHD, serde_json::json!(1.05)),
+        ] {
+            let capture_request = serde_json::to_value(DlocalPaymentsCaptureRequest {
+                authorization_id: "D-4-1".to_string(),
+                amount: to_major_unit(minor_amount, currency),
+                currency: currency.to_string(),
+                order_id: "pay_1".to_string(),
+            })
+            .ok();
+            assert_eq!(
+                capture_request.and_then(|request| request.get("amount").cloned()),
+                Some(major_amount.clone()),
+                "capture of {minor_amount} {currency}"
+            );
+            let refund_request = serde_json::to_value(DlocalRefundRequest {
+                amount: to_major_unit(minor_amount, currency),
+                payment_id: "D-4-1".to_string(),
+                currency,
+                id: "ref_1".to_string(),
+                notification_url: None,
+            })
+            .ok();
+            assert_eq!(
+                refund_request.and_then(|request| request.get("amount").cloned()),
+                Some(major_amount),
+                "refund of {minor_amount} {currency}"
+            );
+        }
+
+        // Refund amounts convert back to the requested minor amount
+        let currencies = [
+            Currency::BRL,
+            Currency::MXN,
+            Currency::COP,
+            Currency::JPY,
+            Currency::CLP,
+            Currency::KWD,
+            Currency::BHD,
+        ];
+        for currency in currencies {
+            for minor_amount in [1, 10, 99, 1000, 1050, 123456, 99999999] {
+                let refund_request = serde_json::to_string(&DlocalRefundRequest {
+                    amount: to_major_unit(minor_amount, currency),
+                    payment_id: "D-4-1".to_string(),
+                    currency,
+                    id: "ref_1".to_string(),
+                    notification_url: None,
+                })
+                .unwrap_or_default();
+                let round_trip = serde_json::from_str::<serde_json::Value>(&refund_request)
+                    .ok()
+                    .and_then(|request| request.get("amount").cloned())
+                    .and_then(|amount| serde_json::from_value::<FloatMajorUnit>(amount).ok())
+                    .and_then(|amount| {
+                        FloatMajorUnitForConnector
+                            .convert_back(amount, currency)
+                            .ok()
+                    });
+                assert_eq!(
+                    round_trip,
+                    Some(MinorUnit::new(minor_amount)),
+                    "{minor_amount} {currency} sent as {refund_request}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_capture_flag() {
+        let capture = |capture_method| should_capture(capture_method).ok();
+        assert_eq!(capture(Some(CaptureMethod::Automatic)), Some(true));
+        assert_eq!(
+            capture(Some(CaptureMethod::SequentialAutomatic)),
+            Some(true)
+        );
+        assert_eq!(capture(None), Some(true));
+        assert_eq!(capture(Some(CaptureMethod::Manual)), Some(false));
+        assert_eq!(capture(Some(CaptureMethod::ManualMultiple)), Some(false));
+        assert!(
+            should_capture(Some(CaptureMethod::Scheduled)).is_err_and(|error| matches!(
+                error.current_context(),
+                ConnectorError::CaptureMethodNotSupported
+            ))
+        );
+
+        let card = serde_json::to_string(&Card {
+            holder_name: Some(Secret::new("John Doe".to_string())),
+            capture: false,
+            ..Default::default()
+        })
+        .ok();
+        assert_eq!(
+            card.as_deref(),
+            Some(r#"{"holder_name":"John Doe","capture":false}"#)
+        );
+    }
+
+    #[test]
+    fn test_cancel_response() {
+        let response = r#"{
+            "id": "D-4-cf4e1fde-2b7a-4b2e-9a8c-8a2f6b1d7c10",
+            "amount": 120,
+            "currency": "BRL",
+            "payment_method_id": "CARD",
+            "payment_method_type": "CARD",
+            "payment_method_flow": "DIRECT",
+            "country": "BR",
+            "created_date": "2024-05-21T13:36:52.000+0000",
+            "status": "CANCELLED",
+            "status_detail": "The payment was cancelled.",
+            "status_code": "400",
+            "order_id": "pay_Uj7fH3UtlHCFhLCjWNBr_1"
+        }"#;
+        let cancel = serde_json::from_str::<DlocalPaymentsCancelResponse>(response).ok();
+        assert_eq!(
+            cancel.as_ref().map(|cancel| cancel.id.as_str()),
+            Some("D-4-cf4e1fde-2b7a-4b2e-9a8c-8a2f6b1d7c10")
+        );
+        assert_eq!(
+            cancel
+                .as_ref()
+                .map(|cancel| AttemptStatus::from(cancel.status.clone())),
+            Some(AttemptStatus::Voided)
+        );
+        assert_eq!(
+            cancel.and_then(|cancel| cancel.order_id),
+            Some("pay_Uj7fH3UtlHCFhLCjWNBr_1".to_string())
+        );
+
+        // dLocal may leave out the order id of a voided payment
+        let response = r#"{"id":"D-4-cf4e1fde","status":"CANCELLED"}"#;
+        let cancel = serde_json::from_str::<DlocalPaymentsCancelResponse>(response).ok();
+        assert_eq!(
+            cancel.map(|cancel| (cancel.id, cancel.order_id)),
+            Some(("D-4-cf4e1fde".to_string(), None))
+        );
+    }
+
+    #[test]
+    fn test_three_dsecure_browser_info() {
+        let browser_info = BrowserInformation {
+            color_depth: Some(24),
+            java_enabled: Some(false),
+            java_script_enabled: Some(true),
+            language: Some("pt-BR".to_string()),
+            screen_height: Some(1080),
+            screen_width: Some(1920),
+            time_zone: Some(-180),
+            accept_header: Some("text/html,application/xhtml+xml".to_string()),
+            user_agent: Some("Mozilla/5.0 (X11; Linux x86_64)".to_string()),
+            ..Default::default()
+        };
+        let three_dsecure = |auth_type, browser_info| {
+            get_three_dsecure(auth_type, browser_info)
+                .ok()
+                .and_then(|three_dsecure| serde_json::to_value(three_dsecure).ok())
+        };
+        assert_eq!(
+            three_dsecure(AuthenticationType::ThreeDs, Some(&browser_info)),
+            Some(serde_json::json!({
+                "force": true,
+                "browser_info": {
+                    "accept_header": "text/html,application/xhtml+xml",
+                    "user_agent": "Mozilla/5.0 (X11; Linux x86_64)",
+                    "language": "pt-BR",
+                    "screen_width": 1920,
+                    "screen_height": 1080,
+                    "color_depth": 24,
+                    "java_enabled": false,
+                    "javascript_enabled": true,
+                    "time_zone": -180
+                }
+            }))
+        );
+        // The device data collection of 3DS 2.x needs the browser info
+        assert!(
+            get_three_dsecure(AuthenticationType::ThreeDs, None).is_err_and(|error| matches!(
+                error.current_context(),
+                ConnectorError::MissingRequiredField {
+                    field_name: "browser_info"
+                }
+            ))
+        );
+        let without_user_agent = BrowserInformation {
+            user_agent: None,
+            ..browser_info.clone()
+        };
+        let three_dsecure_error =
+            get_three_dsecure(AuthenticationType::ThreeDs, Some(&without_user_agent));
+        assert!(three_dsecure_error.is_err_and(|error| matches!(
+            error.current_context(),
+            ConnectorError::MissingRequiredField {
+                field_name: "browser_info.user_agent"
+            }
+        )));
+        // The browser info is only sent along with 3DS
+        assert_eq!(
+            three_dsecure(AuthenticationType::NoThreeDs, Some(&browser_info)),
+            Some(serde_json::Value::Null)
+        );
+    }
+
+    #[test]
+    fn test_rejected_sync_response_error() {
+        let response = r#"{
+            "id": "D-4-9a3e5f5b-1c3a-4e0f-8f6a-0b6f8d5c2e11",
+            "status": "REJECTED",
+            "status_code": "302",
+            "status_detail": "Insufficient amount."
+        }"#;
+        let error_response = serde_json::from_str::<DlocalPaymentsResponse>(response)
+            .ok()
+            .and_then(|response| {
+                get_rejected_payment_error_response(
+                    &response.status,
+                    &response.id,
+                    response.status_code.as_ref(),
+                    response.status_detail.as_ref(),
+                    200,
+                )
+            });
+        assert_eq!(
+            error_response.as_ref().map(|error_response| (
+                error_response.code.as_str(),
+                error_response.message.as_str(),
+                error_response.connector_transaction_id.as_deref()
+            )),
+            Some((
+                "302",
+                "Insufficient amount.",
+                Some("D-4-9a3e5f5b-1c3a-4e0f-8f6a-0b6f8d5c2e11")
+            ))
+        );
+
+        // Only rejected payments are turned into an error
+        let response = r#"{"id":"D-4-1","status":"PAID","status_code":"200"}"#;
+        let error_response = serde_json::from_str::<DlocalPaymentsResponse>(response)
+            .ok()
+            .and_then(|response| {
+                get_rejected_payment_error_response(
+                    &response.status,
+                    &response.id,
+                    response.status_code.as_ref(),
+                    response.status_detail.as_ref(),
+                    200,
+                )
+            });
+        assert!(error_response.is_none());
+    }
+
+    #[test]
+    fn test_network_txn_id() {
+        let response = r#"{
+            "id": "D-4-e2227981-8ec8-48fd-8e9a-19fedb08d73a",
+            "amount": 120,
+            "currency": "BRL",
+            "status": "PAID",
+            "order_id": "pay_Uj7fH3UtlHCFhLCjWNBr_1",
+            "card": {
+                "card_id": "CV-ecd897ac-5361-45a1-a407-aaab044ce87e",
+                "network_tx_reference": "MCC000000355",
+                "brand": "VI",
+                "last4": "1111"
+            }
+        }"#;
+        let payment = serde_json::from_str::<DlocalPaymentsResponse>(response).ok();
+        assert_eq!(
+            payment.and_then(|payment| get_network_txn_id(payment.card.as_ref())),
+            Some("MCC000000355".to_string())
+        );
+        let sync = serde_json::from_str::<DlocalPaymentsResponse>(response).ok();
+        assert_eq!(
+            sync.and_then(|sync| get_network_txn_id(sync.card.as_ref())),
+            Some("MCC000000355".to_string())
+        );
+        let capture = serde_json::from_str::<DlocalPaymentsCaptureResponse>(response).ok();
+        assert_eq!(
+            capture.and_then(|capture| get_network_txn_id(capture.card.as_ref())),
+            Some("MCC000000355".to_string())
+        );
+
+        // Payments made with other payment methods carry no network reference
+        let response = r#"{"id":"D-4-1","status":"PENDING"}"#;
+        let payment = serde_json::from_str::<DlocalPaymentsResponse>(response).ok();
+        assert_eq!(
+            payment.map(|payment| get_network_txn_id(payment.card.as_ref())),
+            Some(None)
+        );
+    }
+
+    #[test]
+    fn test_incremental_authorization_allowed() {
+        let response = r#"{"id":"D-4-1","status":"AUTHORIZED","status_code":"600"}"#;
+        let payment = serde_json::from_str::<DlocalPaymentsResponse>(response).ok();
+        assert_eq!(
+            payment.map(|payment| get_incremental_authorization_allowed(&payment.status)),
+            Some(Some(false))
+        );
+
+        // Only an authorization could be raised, other payments leave it to the router
+        let response = r#"{"id":"D-4-1","status":"PAID","status_code":"200"}"#;
+        let sync = serde_json::from_str::<DlocalPaymentsResponse>(response).ok();
+        assert_eq!(
+            sync.map(|sync| get_incremental_authorization_allowed(&sync.status)),
+            Some(None)
+        );
+    }
+
+    #[test]
+    fn test_error_code_mapping() {
+        let cases = [
+            (
+                308,
+                None,
+                "INVALID_CARD",
+                "Invalid card",
+                Some(AttemptStatus::Failure),
+            ),
+            (
+                309,
+                None,
+                "INVALID_CARD",
+                "Invalid card",
+                Some(AttemptStatus::Failure),
+            ),
+            (
+                311,
+                None,
+                "INVALID_CARD",
+                "Invalid card",
+                Some(AttemptStatus::Failure),
+            ),
+            (
+                312,
+                None,
+                "INVALID_CARD",
+                "Invalid card",
+                Some(AttemptStatus::Failure),
+            ),
+            (
+                314,
+                None,
+                "INVALID_CARD",
+                "Invalid card",
+                Some(AttemptStatus::Failure),
+            ),
+            (
+                315,
+                None,
+                "INVALID_CARD",
+                "Invalid card",
+                Some(AttemptStatus::Failure),
+            ),
+            (302, None, "INSUFFICIENT_FUNDS", "Insufficient funds", None),
+            (306, None, "ISSUER_UNAVAILABLE", "Issuer unavailable", None),
+            (7000, None, "ISSUER_UNAVAILABLE", "Issuer unavailable", None),
+            (
+                303,
+                None,
+                "FRAUD_DECLINE",
+                "Declined as fraudulent",
+                Some(AttemptStatus::Failure),
+            ),
+            (
+                304,
+                None,
+                "FRAUD_DECLINE",
+                "Declined as fraudulent",
+                Some(AttemptStatus::Failure),
+            ),
+            (
+                310,
+                None,
+                "FRAUD_DECLINE",
+                "Declined as fraudulent",
+                Some(AttemptStatus::Failure),
+            ),
+            (
+                313,
+                None,
+                "FRAUD_DECLINE",
+                "Declined as fraudulent",
+                Some(AttemptStatus::Failure),
+            ),
+            (
+                317,
+                None,
+                "FRAUD_DECLINE",
+                "Declined as fraudulent",
+                Some(AttemptStatus::Failure),
+            ),
+            (
+                5000,
+                None,
+                "INVALID_REQUEST_FIELD",
+                "Invalid request field",
+                Some(AttemptStatus::Failure),
+            ),
+            (
+                5001,
+                Some("payer.document"),
+                "INVALID_REQUEST_FIELD",
+                "Invalid request field: payer.document",
+                Some(AttemptStatus::Failure),
+            ),
+            (
+                5002,
+                None,
+                "INVALID_REQUEST_FIELD",
+                "Invalid request field",
+                Some(AttemptStatus::Failure),
+            ),
+        ];
+        for (code, param, expected_category, expected_message, expected_status) in cases {
+            let error_response = DlocalErrorResponse {
+                code,
+                message: "dLocal message".to_string(),
+                param: param.map(str::to_string),
+                retry_after: None,
+            }
+            .get_error_response(400);
+            assert_eq!(error_response.code, code.to_string());
+            assert_eq!(error_response.message, "dLocal message", "code {code}");
+            assert_eq!(
+                error_response.reason,
+                Some(format!("{expected_category}: {expected_message}")),
+                "code {code}"
+            );
+            assert_eq!(
+                error_response.attempt_status, expected_status,
+                "code {code}"
+            );
+            assert_eq!(
+                expected_category.parse::<DlocalErrorCategory>().ok(),
+                DlocalErrorCategory::from_code(code)
+            );
+        }
+
+        // Unknown codes keep dLocal's code and message, declines still fail the attempt
+        let error_response = DlocalErrorResponse {
+            code: 5003,
+            message: "Country not supported".to_string(),
+            param: None,
+            retry_after: None,
+        }
+        .get_error_response(400);
+        assert_eq!(
+            (
+                error_response.code.as_str(),
+                error_response.message.as_str()
+            ),
+            ("5003", "Country not supported")
+        );
+        assert_eq!(error_response.attempt_status, None);
+        let error_response = DlocalErrorResponse {
+            code: 300,
+            message: "The payment was rejected.".to_string(),
+            param: None,
+            retry_after: None,
+        }
+        .get_error_response(400);
+        assert_eq!(error_response.code, "300");
+        assert_eq!(error_response.attempt_status, Some(AttemptStatus::Failure));
+    }
+
+    #[test]
+    fn test_cancel_request_and_partial_cancel() {
+        let request = serde_json::to_value(DlocalPaymentsCancelRequest {
+            cancel_id: "D-4-1".to_string(),
+            amount: Some(to_major_unit(5000, Currency::BRL)),
+            currency: Some(Currency::BRL),
+        })
+        .ok();
+        assert_eq!(
+            request,
+            Some(serde_json::json!({"amount": 50.0, "currency": "BRL"}))
+        );
+        let request = serde_json::to_value(DlocalPaymentsCancelRequest {
+            cancel_id: "D-4-1".to_string(),
+            ..Default::default()
+        })
+        .ok();
+        assert_eq!(request, Some(serde_json::json!({})));
+
+        let authorized_amount = Some(MinorUnit::new(12000));
+        assert!(validate_cancel_amount(Some(MinorUnit::new(5000)), authorized_amount).is_ok());
+        assert!(validate_cancel_amount(Some(MinorUnit::new(12000)), authorized_amount).is_ok());
+        assert!(validate_cancel_amount(Some(MinorUnit::new(12001)), authorized_amount).is_err());
+        // Payments authorized before the amount was kept in the metadata
+        assert!(validate_cancel_amount(Some(MinorUnit::new(12001)), None).is_ok());
+
+        let response = r#"{"id":"D-4-1","status":"CANCELLED","amount":50,"currency":"BRL"}"#;
+        let cancel = serde_json::from_str::<DlocalPaymentsCancelResponse>(response).ok();
+        assert_eq!(
+            cancel
+                .as_ref()
+                .and_then(|cancel| cancel.get_attempt_status(authorized_amount).ok()),
+            Some(AttemptStatus::Authorized)
+        );
+        assert_eq!(
+            cancel.and_then(|cancel| cancel.get_attempt_status(Some(MinorUnit::new(5000))).ok()),
+            Some(AttemptStatus::Voided)
+        );
+
+        // Without the cancelled amount the whole authorization is taken as cancelled
+        let response = r#"{"id":"D-4-1","status":"CANCELLED"}"#;
+        let cancel = serde_json::from_str::<DlocalPaymentsCancelResponse>(response).ok();
+        assert_eq!(
+            cancel.and_then(|cancel| cancel.get_attempt_status(authorized_amount).ok()),
+            Some(AttemptStatus::Voided)
+        );
+    }
+
+    #[test]
+    fn test_validate_supported_country() {
+        assert!(validate_supported_country(CountryAlpha2::BR).is_ok());
+        assert!(validate_supported_country(CountryAlpha2::IN).is_ok());
+        let unsupported = validate_supported_country(CountryAlpha2::US);
+        assert!(unsupported.is_err_and(|error| matches!(
+            error.current_context(),
+            ConnectorError::NotSupported { message, .. } if message == "billing country US"
+        )));
+    }
+
+    #[test]
+    fn test_document_mode() {
+        let sandbox = serde_json::json!({"document_mode": "SANDBOX"});
+        let sandbox = DlocalConnectorMetadataObject::try_from(&Some(Secret::new(sandbox)))
+            .map(|metadata| metadata.document_mode);
+        assert!(sandbox.is_ok_and(|document_mode| document_mode == DlocalDocumentMode::Sandbox));
+        let production = serde_json::json!({"card_payment_flow": "DIRECT"});
+        let production = DlocalConnectorMetadataObject::try_from(&Some(Secret::new(production)))
+            .map(|metadata| metadata.document_mode);
+        assert!(
+            production.is_ok_and(|document_mode| document_mode == DlocalDocumentMode::Production)
+        );
+
+        // A supplied document is always sent, the mode only decides about a missing one
+        let supplied = Some(Secret::new("53033315550".to_string()));
+        for document_mode in [DlocalDocumentMode::Sandbox, DlocalDocumentMode::Production] {
+            let document = supplied
+                .clone()
+                .or_else(|| document_mode.get_placeholder_document(CountryAlpha2::BR));
+            assert_eq!(
+                get_payer_document(document)
+                    .ok()
+                    .map(|document| document.expose()),
+                Some("53033315550".to_string())
+            );
+        }
+
+        let document = DlocalDocumentMode::Sandbox.get_placeholder_document(CountryAlpha2::BR);
+        assert_eq!(
+            get_payer_document(document)
+                .ok()
+                .map(|document| document.expose()),
+            Some("91483309223".to_string())
+        );
+        let document = DlocalDocumentMode::Production.get_placeholder_document(CountryAlpha2::BR);
+        assert!(get_payer_document(document).is_err_and(|error| matches!(
+            error.current_context(),
+            ConnectorError::MissingRequiredField {
+                field_name: "metadata.document"
+            }
+        )));
+    }
+
+    #[test]
+    fn test_refund_request_notification_url() {
+        let refund_request = serde_json::to_value(DlocalRefundRequest {
+            amount: to_major_unit(1000, Currency::BRL),
+            payment_id: "D-4-1".to_string(),
+            currency: Currency::BRL,
+            id: "ref_1".to_string(),
+            notification_url: Some("https://merchant.example/webhooks/dlocal".to_string()),
+        })
+        .ok();
+        assert_eq!(
+            refund_request,
+            Some(serde_json::json!({
+                "amount": 10.0,
+                "payment_id": "D-4-1",
+                "currency": "BRL",
+                "id": "ref_1",
+                "notification_url": "https://merchant.example/webhooks/dlocal"
+            }))
+        );
+    }
+
+    #[test]
+    fn test_refund_response_reconciliation_fields() {
+        let response = r#"{
+            "id": "REF-15104-a9cc29ef-1041-4d5f-8e4d-2d1e6b7e3f25",
+            "payment_id": "D-15104-9f5246d5-34e2-4f63-9d29-380ab1567ec8",
+            "status": "SUCCESS",
+            "currency": "BRL",
+            "amount": 25.5,
+            "created_date": "2024-05-21T12:30:00.000+0000"
+        }"#;
+        let refund = serde_json::from_str::<RefundResponse>(response).ok();
+        assert_eq!(
+            refund.as_ref().and_then(|refund| refund.created_date),
+            to_utc_date_time(2024, time::Month::May, 21, (12, 30, 0))
+        );
+        let integrity_object =
+            refund.and_then(|refund| refund.get_integrity_object().ok().flatten());
+        // A refunded amount that differs from the requested one is flagged by the router's
+        // refund integrity check
+        assert_eq!(
+            integrity_object.map(|integrity_object| {
+                (integrity_object.refund_amount, integrity_object.currency)
+            }),
+            Some((MinorUnit::new(2550), Currency::BRL))
+        );
+
+        // Older responses without the amount and date are still accepted
+        let response = r#"{"id":"REF-15104-1","status":"PENDING"}"#;
+        let refund = serde_json::from_str::<RefundResponse>(response).ok();
+        assert!(refund
+            .as_ref()
+            .is_some_and(|refund| refund.created_date.is_none()));
+        let integrity_object =
+            refund.and_then(|refund| refund.get_integrity_object().ok().flatten());
+        assert!(integrity_object.is_none());
+    }
+
+    #[test]
+    fn test_rate_limit_error() {
+        let response = br#"{"code":429,"message":"Too many requests","retry_after":30}"#;
+        let error_response = get_rate_limit_error_response(response, Some("60"), 429);
+        assert_eq!(
+            (
+                error_response.code.as_str(),
+                error_response.message.as_str()
+            ),
+            ("429", "Too many requests")
+        );
+        assert_eq!(
+            error_response.reason.as_deref(),
+            Some("Rate limited by dLocal, retry after 30 seconds")
+        );
+        assert_eq!(error_response.attempt_status, None);
+        // The header is used when the body has no retry hint
+        let response = br#"{"code":429,"message":"Too many requests"}"#;
+        assert_eq!(
+            get_rate_limit_error_response(response, Some(" 60 "), 429).reason,
+            Some("Rate limited by dLocal, retry after 60 seconds".to_string())
+        );
+        let error_response = get_rate_limit_error_response(b"Too many requests", None, 429);
+        assert_eq!(error_response.code, "No error code");
+        assert_eq!(
+            error_response.reason.as_deref(),
+            Some("Rate limited by dLocal")
+        );
+
+        // A 429 is reported as an error response rather than failing the connector call
+        let mut headers = http::HeaderMap::new();
+        headers.insert("Retry-After", http::HeaderValue::from_static("45"));
+        let error_response = Dlocal::new()
+            .build_error_response(
+                Response {
+                    headers: Some(headers),
+                    response: "Too many requests".into(),
+                    status_code: 429,
+                },
+                None,
+            )
+            .ok();
+        assert!(error_response.is_some_and(|error_response| {
+            error_response.status_code == 429
+                && error_response.reason.as_deref()
+                    == Some("Rate limited by dLocal, retry after 45 seconds")
+        }));
+    }
+
+    #[test]
+    fn test_save_card_for_future_use() {
+        let save = |setup_future_usage| {
+            serde_json::to_value(Card {
+                save: should_save_card(setup_future_usage).then_some(true),
+                installments: Some("3".to_string()),
+                ..Default::default()
+            })
+            .ok()
+            .and_then(|card| card.get("save").cloned())
+        };
+        assert_eq!(
+            save(Some(FutureUsage::OffSession)),
+            Some(serde_json::json!(true))
+        );
+        // Installments alone don't save the card
+        assert_eq!(save(Some(FutureUsage::OnSession)), None);
+        assert_eq!(save(None), None);
+
+        let response = r#"{
+            "id": "D-4-cf8f4a5a-8d2c-4cb5-b4f0-3cc1e4a2b8a1",
+            "status": "PAID",
+            "card": { "card_id": "CV-ecd897ac-5361-45c1-a407-aaab044ce87e" }
+        }"#;
+        let mandate_reference = serde_json::from_str::<DlocalPaymentsResponse>(response)
+            .ok()
+            .and_then(|response| {
+                get_mandate_reference(response.card, Some(FutureUsage::OffSession))
+            });
+        assert_eq!(
+            mandate_reference.and_then(|mandate_reference| mandate_reference.connector_mandate_id),
+            Some("CV-ecd897ac-5361-45c1-a407-aaab044ce87e".to_string())
+        );
+    }
+
+    #[test]
+    fn test_refund_status_mapping() {
+        let refund = |response: &str| {
+            serde_json::from_str::<RefundResponse>(response)
+                .ok()
+                .map(|refund| {
+                    let error_response = refund.get_error_response(200);
+                    (error_response, RefundStatus::from(refund.status))
+                })
+        };
+        let status = |response: &str| {
+            refund(response).map(|(error_response, status)| (error_response.is_none(), status))
+        };
+        let success = status(r#"{"id":"REF-1","status":"SUCCESS","status_code":"200"}"#);
+        assert_eq!(success, Some((true, RefundStatus::Success)));
+        let pending = status(r#"{"id":"REF-1","status":"PENDING","status_code":"100"}"#);
+        assert_eq!(pending, Some((true, RefundStatus::Pending)));
+        let cancelled = status(
+            r#"{"id":"REF-1","status":"CANCELLED","status_code":"400",
+            "status_detail":"The refund was cancelled."}"#,
+        );
+        assert_eq!(cancelled, Some((true, RefundStatus::ManualReview)));
+
+        let rejected = refund(
+            r#"{"id":"REF-1","status":"REJECTED","status_code":"701",
+            "status_detail":"The payment has a chargeback."}"#,
+        );
+        assert_eq!(
+            rejected.as_ref().map(|(_, status)| *status),
+            Some(RefundStatus::Failure)
+        );
+        assert_eq!(
+            rejected
+                .and_then(|(error_response, _)| error_response)
+                .map(|error_response| (
+                    error_response.code,
+                    error_response.message,
+                    error_response.connector_transaction_id
+                )),
+            Some((
+                "701".to_string(),
+                "The payment has a chargeback.".to_string(),
+                Some("REF-1".to_string())
+            ))
+        );
+    }
+
+    #[test]
+    fn test_notification_url() {
+        let request = |notification_url: Option<&str>| {
+            serde_json::to_value(DlocalPaymentsRequest {
+                notification_url: notification_url.map(str::to_string),
+                ..Default::default()
+            })
+            .ok()
+        };
+        assert_eq!(
+            request(Some("https://merchant.example/webhooks/dlocal"))
+                .as_ref()
+                .and_then(|request| request.get("notification_url")),
+            Some(&serde_json::json!(
+                "https://merchant.example/webhooks/dlocal"
+            ))
+        );
+        // dLocal rejects a `null` notification url
+        assert!(request(None).is_some_and(|request| request.get("notification_url").is_none()));
+    }
+
+    #[test]
+    fn test_google_pay_wallet_card() {
+        let mut decrypted_data = serde_json::json!({
+            "messageExpiration": "1735689600000",
+            "messageId": "message_id",
+            "paymentMethod": "CARD",
+            "paymentMethodDetails": {
+                "authMethod": "CRYPTOGRAM_3DS",
+                "expirationMonth": 7,
+                "expirationYear": 2099,
+                "pan": "4111111111111111",
+                "cryptogram": "AgAAAAAAAIR8CQrXcIhbQAAAAAA=",
+                "eciIndicator": "05"
+            }
+        });
+        let google_pay_token = |data: &serde_json::Value| {
+            serde_json::from_value::<GooglePayDecryptedData>(data.clone())
+                .ok()
+                .map(|data| PaymentMethodToken::GooglePayDecrypt(Box::new(data)))
+        };
+
+        let card = get_wallet_card(google_pay_token(&decrypted_data).as_ref()).ok();
+        assert_eq!(
+            card.as_ref()
+                .and_then(|card| card.network_token.as_ref())
+                .map(|token| token.peek().clone()),
+            Some("4111111111111111".to_string())
+        );
+        assert_eq!(
+            card.as_ref()
+                .and_then(|card| card.cryptogram.clone())
+                .map(ExposeInterface::expose),
+            Some("AgAAAAAAAIR8CQrXcIhbQAAAAAA=".to_string())
+        );
+        assert_eq!(
+            card.as_ref()
+                .and_then(|card| card.expiration_month.clone())
+                .map(ExposeInterface::expose),
+            Some("07".to_string())
+        );
+        assert_eq!(card.as_ref().and_then(|card| card.tokenized), Some(true));
+
+        if let Some(cryptogram) = decrypted_data.pointer_mut("/paymentMethodDetails/cryptogram") {
+            *cryptogram = serde_json::Value::Null;
+        }
+        assert!(
+            get_wallet_card(google_pay_token(&decrypted_data).as_ref()).is_err_and(
+                |error| matches!(
+                    error.current_context(),
+                    ConnectorError::MissingRequiredField {
+                        field_name: "google_pay.cryptogram"
+                    }
+                )
+            )
+        );
+        assert!(get_wallet_card(None).is_err_and(|error| matches!(
+            error.current_context(),
+            ConnectorError::MissingRequiredField {
+                field_name: "decrypted_wallet_token"
+            }
+        )));
+    }
+
+    #[test]
+    fn test_fraud_data() {
+        let browser_info = BrowserInformation {
+            ip_address: "181.43.12.7".parse().ok(),
+            ..Default::default()
+        };
+        let fraud = get_fraud_data(
+            Some(Secret::new(
+                "f0b4b0ec-5f8c-4b8e-9a6a-4c1f0b7e2d11".to_string(),
+            )),
+            Some("session_4b1e".to_string()),
+            Some(&browser_info),
+        );
+        let request = serde_json::to_value(DlocalPaymentsRequest {
+            fraud,
+            ..Default::default()
+        })
+        .ok();
+        assert_eq!(
+            request.as_ref().and_then(|request| request.get("fraud")),
+            Some(&serde_json::json!({
+                "device_id": "f0b4b0ec-5f8c-4b8e-9a6a-4c1f0b7e2d11",
+                "session_id": "session_4b1e",
+                "ip": "181.43.12.7"
+            }))
+        );
+
+        let fraud = get_fraud_data(None, None, Some(&BrowserInformation::default()));
+        assert_eq!(fraud, None);
+        let request = serde_json::to_value(DlocalPaymentsRequest {
+            fraud,
+            ..Default::default()
+        })
+        .ok();
+        assert!(request.is_some_and(|request| request.get("fraud").is_none()));
+    }
+
+    #[test]
+    fn test_reference_id() {
+        assert_eq!(
+            get_dlocal_reference_id("pay_Uj7fH3UtlHCFhLCjWNBr_1", "refund_id").ok(),
+            Some("pay_Uj7fH3UtlHCFhLCjWNBr_1".to_string())
+        );
+        assert_eq!(
+            get_dlocal_reference_id("order #42/ñ.1", "connector_request_reference_id").ok(),
+            Some("order__42___1".to_string())
+        );
+        assert_eq!(
+            get_dlocal_reference_id(&"a".repeat(128), "refund_id").ok(),
+            Some("a".repeat(128))
+        );
+        assert!(
+            get_dlocal_reference_id(&"a".repeat(129), "refund_id").is_err_and(|error| matches!(
+                error.current_context(),
+                ConnectorError::MaxFieldLengthViolated {
+                    connector: "Dlocal",
+                    field_name: "refund_id",
+                    max_length: 128,
+                    received_length: 129,
+                }
+            ))
+        );
+    }
+
+    #[test]
+    fn test_validate_currency_for_country() {
+        assert!(validate_currency_for_country(CountryAlpha2::BR, Currency::BRL).is_ok());
+        assert!(validate_currency_for_country(CountryAlpha2::CO, Currency::USD).is_ok());
+        assert!(
+            validate_currency_for_country(CountryAlpha2::CO, Currency::BRL).is_err_and(|error| {
+                matches!(
+                    error.current_context(),
+                    ConnectorError::NotSupported { message, connector: "Dlocal" }
+                        if message == "BRL payments for billing country CO"
+                )
+            })
+        );
+        // Countries without an entry are left to dLocal
+        assert!(validate_currency_for_country(CountryAlpha2::US, Currency::EUR).is_ok());
+    }
+
+    #[test]
+    fn test_balance_response() {
+        let response = serde_json::from_str::<DlocalBalanceResponse>(
+            r#"{
+                "balances": [
+                    {
+                        "currency": "USD",
+                        "available_balance": 1520.75,
+                        "pending_settlement": 310.5
+                    },
+                    { "currency": "BRL", "available_balance": 8200 }
+                ]
+            }"#,
+        )
+        .ok();
+        let balances = response.map(|response| {
+            response
+                .balances
+                .into_iter()
+                .map(|balance| {
+                    (
+                        balance.currency,
+                        balance.available_balance,
+                        balance.pending_settlement,
+                    )
+                })
+                .collect::<Vec<_>>()
+        });
+        assert_eq!(
+            balances,
+            Some(vec![
+                (
+                    Currency::USD,
+                    to_major_unit(152075, Currency::USD),
+                    to_major_unit(31050, Currency::USD)
+                ),
+                // Currencies without a pending settlement have nothing left to settle
+                (
+                    Currency::BRL,
+                    to_major_unit(820000, Currency::BRL),
+                    FloatMajorUnit::zero()
+                ),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_auth_type_is_redacted() {
+        let auth = DlocalAuthType {
+            x_login: Secret::new("sak223k2wdksdl2".to_string()),
+            x_trans_key: Secret::new("fm12O7G9".to_string()),
+            secret: Secret::new("dlocal_secret".to_string()),
+        };
+        let debug = format!("{auth:?}");
+        assert_eq!(
+            debug,
+            "DlocalAuthType { x_login: ***, x_trans_key: ***, secret: *** }"
+        );
+        assert_eq!(auth.to_string(), debug);
+        for credential in [
+            "sak223k2wdksdl2",
+            "fm12O7G9",
+            "dlocal_secret",
+            "sak2",
+            "O7G9",
+        ] {
+            assert!(!debug.contains(credential));
+        }
+    }
+
+    #[test]
+    fn test_payer_phone() {
+        let phone = |number: &str, country_code: Option<&str>| {
+            get_payer_phone(Some(&PhoneDetails {
+                number: Some(Secret::new(number.to_string())),
+                country_code: country_code.map(str::to_string),
+            }))
+            .map(ExposeInterface::expose)
+        };
+        assert_eq!(
+            phone("(11) 98765-4321", Some("+55")),
+            Some("5511987654321".to_string())
+        );
+        // A number with a leading `+` already carries its country code
+        assert_eq!(
+            phone("+91 98765 43210", Some("+91")),
+            Some("919876543210".to_string())
+        );
+        assert_eq!(
+            phone("0803 123 4567", None),
+            Some("08031234567".to_string())
+        );
+        assert_eq!(phone("-", Some("")), None);
+        assert_eq!(get_payer_phone(None), None);
+
+        let payer = serde_json::to_value(Payer::default()).ok();
+        assert!(payer.is_some_and(|payer| payer.get("phone").is_none()));
+    }
+
+    #[test]
+    fn test_payer_address() {
+        let address = AddressDetails {
+            city: Some("São Paulo".to_string()),
+            country: Some(CountryAlpha2::BR),
+            line1: Some(Secret::new("Avenida Paulista".to_string())),
+            line2: Some(Secret::new("1000".to_string())),
+            zip: Some(Secret::new("01310-100".to_string())),
+            state: Some(Secret::new("SP".to_string())),
+            ..Default::default()
+        };
+        assert_eq!(
+            serde_json::to_value(get_payer_address(&address)).ok(),
+            Some(serde_json::json!({
+                "state": "SP",
+                "city": "São Paulo",
+                "zip_code": "01310-100",
+                "street": "Avenida Paulista",
+                "number": "1000"
+            }))
+        );
+
+        // Missing fields are left out
+        let address = AddressDetails {
+            zip: Some(Secret::new("01310-100".to_string())),
+            ..Default::default()
+        };
+        assert_eq!(
+            serde_json::to_value(get_payer_address(&address)).ok(),
+            Some(serde_json::json!({ "zip_code": "01310-100" }))
+        );
+
+        // An address with neither a city nor a zip code isn't sent
+        let address = AddressDetails {
+            line1: Some(Secret::new("Avenida Paulista".to_string())),
+            country: Some(CountryAlpha2::BR),
+            ..Default::default()
+        };
+        assert_eq!(get_payer_address(&address), None);
+    }
+
+    #[test]
+    fn test_payment_method_flow() {
+        assert_eq!(
+            get_payment_method_flow(PaymentMethodId::Card, AuthenticationType::ThreeDs),
+            PaymentMethodFlow::ReDirect
+        );
+        assert_eq!(
+            get_payment_method_flow(PaymentMethodId::Card, AuthenticationType::NoThreeDs),
+            PaymentMethodFlow::Direct
+        );
+        assert_eq!(
+            get_payment_method_flow(PaymentMethodId::Pix, AuthenticationType::NoThreeDs),
+            PaymentMethodFlow::ReDirect
+        );
+        assert_eq!(
+            get_payment_method_flow(PaymentMethodId::Boleto, AuthenticationType::NoThreeDs),
+            PaymentMethodFlow::Direct
+        );
+    }
+
+    #[test]
+    fn test_payer_ip_and_device_id() {
+        let payer = serde_json::to_value(Payer {
+            ip: Some(Secret::new("181.43.12.7".to_string())),
+            device_id: Some(Secret::new(
+                "f0b4b0ec-5f8c-4b8e-9a6a-4c1f0b7e2d11".to_string(),
+            )),
+            ..Default::default()
+        })
+        .ok();
+        assert_eq!(
+            payer.as_ref().and_then(|payer| payer.get("ip")),
+            Some(&serde_json::json!("181.43.12.7"))
+        );
+        assert_eq!(
+            payer.as_ref().and_then(|payer| payer.get("device_id")),
+            Some(&serde_json::json!("f0b4b0ec-5f8c-4b8e-9a6a-4c1f0b7e2d11"))
+        );
+
+        // Server to server payments have no browser info to take them from
+        let payer = serde_json::to_value(Payer::default()).ok();
+        assert!(payer.is_some_and(|payer| {
+            payer.get("ip").is_none() && payer.get("device_id").is_none()
+        }));
+    }
+
+    #[test]
+    fn test_post_redirect_form() {
+        let three_dsecure = serde_json::from_str::<ThreeDSecureResData>(
+            r#"{
+                "redirect_url": "https://acs.issuer.example/challenge",
+                "redirect_method": "POST",
+                "redirect_params": { "creq": "eyJ0aHJlZURTU2VydmVyVHJhbnNJRCI6IjEifQ" }
+            }"#,
+        )
+        .ok();
+        assert!(matches!(
+            get_redirection_data(three_dsecure, None),
+            Some(RedirectForm::Form { endpoint, method: Method::Post, form_fields })
+                if endpoint == "https://acs.issuer.example/challenge"
+                    && form_fields.get("creq").map(String::as_str)
+                        == Some("eyJ0aHJlZURTU2VydmVyVHJhbnNJRCI6IjEifQ")
+        ));
+
+        // 3DS 1.0 acquirers post the PaReq and MD
+        let three_dsecure = serde_json::from_str::<ThreeDSecureResData>(
+            r#"{
+                "redirect_url": "https://acs.acquirer.example/pareq",
+                "method": "POST",
+                "parameters": { "PaReq": "eJxVUttuwjAM", "MD": "D-4-1" }
+            }"#,
+        )
+        .ok();
+        assert!(matches!(
+            get_redirection_data(three_dsecure, None),
+            Some(RedirectForm::Form { method: Method::Post, form_fields, .. })
+                if form_fields.get("PaReq").map(String::as_str) == Some("eJxVUttuwjAM")
+                    && form_fields.get("MD").map(String::as_str) == Some("D-4-1")
+        ));
+
+        // Without a method the redirect url is followed with a GET
+        let three_dsecure = serde_json::from_str::<ThreeDSecureResData>(
+            r#"{ "redirect_url": "https://sandbox.dlocal.com/3ds?id=D-4-1" }"#,
+        )
+        .ok();
+        assert!(matches!(
+            get_redirection_data(three_dsecure, None),
+            Some(RedirectForm::Form { method: Method::Get, form_fields, .. })
+                if form_fields.get("id").map(String::as_str) == Some("D-4-1")
+        ));
+    }
+
+    #[cfg(feature = "payouts")]
+    #[test]
+    fn test_payout_create_request() {
+        let request = serde_json::to_value(DlocalPayoutCreateRequest {
+            external_id: "payout_7Jk2mP".to_string(),
+            amount: to_major_unit(25050, Currency::BRL),
+            currency: Currency::BRL,
+            country: CountryAlpha2::BR,
+            beneficiary: DlocalPayoutBeneficiary {
+                first_name: Secret::new("Maria".to_string()),
+                last_name: Some(Secret::new("Silva".to_string())),
+                email: None,
+                document: Secret::new("53033315550".to_string()),
+                bank_account: DlocalPayoutBankAccount {
+                    bank_name: Some("Banco do Brasil".to_string()),
+                    branch: Some("1234".to_string()),
+                    account: Secret::new("123456789".to_string()),
+                    pix_key: Some(Secret::new("maria@example.com".to_string())),
+                },
+            },
+            on_hold: true,
+        })
+        .ok();
+        assert_eq!(
+            request,
+            Some(serde_json::json!({
+                "external_id": "payout_7Jk2mP",
+                "amount": 250.5,
+                "currency": "BRL",
+                "country": "BR",
+                "beneficiary": {
+                    "first_name": "Maria",
+                    "last_name": "Silva",
+                    "email": null,
+                    "document": "53033315550",
+                    "bank_account": {
+                        "bank_name": "Banco do Brasil",
+                        "branch": "1234",
+                        "account": "123456789",
+                        "pix_key": "maria@example.com"
+                    }
+                },
+                "on_hold": true
+            }))
+        );
+    }
+
+    #[test]
+    fn test_external_three_dsecure() {
+        let authentication_data = AuthenticationData {
+            eci: Some("05".to_string()),
+            cavv: "AAABBEg0VhI0VniQEjRWAAAAAAA=".to_string(),
+            threeds_server_transaction_id: "3ds_server_txn".to_string(),
+            message_version: SemanticVersion::new(2, 2, 0),
+            ds_trans_id: Some("ds_txn".to_string()),
+        };
+        let three_dsecure =
+            ThreeDSecureReqData::External(DlocalExternalThreeDSecure::from(&authentication_data));
+        assert_eq!(
+            serde_json::to_value(three_dsecure).ok(),
+            Some(serde_json::json!({
+                "cavv": "AAABBEg0VhI0VniQEjRWAAAAAAA=",
+                "eci": "05",
+                "ds_transaction_id": "ds_txn",
+                "three_dsecure_version": "2.2.0"
+            }))
+        );
+        // Nothing is left for the payer to complete, so the payment isn't pending authentication
+        assert_eq!(
+            get_attempt_status(DlocalPaymentStatus::Pending, None, false),
+            AttemptStatus::Pending
+        );
+    }
+
+    #[test]
+    fn test_payment_created_date() {
+        let response = r#"{
+            "id": "D-4-1",
+            "status": "PAID",
+            "created_date": "2024-05-21T10:30:00-03:00"
+        }"#;
+        let payment = serde_json::from_str::<DlocalPaymentsResponse>(response).ok();
+        assert_eq!(
+            payment.as_ref().and_then(|payment| payment.created_date),
+            to_utc_date_time(2024, time::Month::May, 21, (13, 30, 0))
+        );
+        let connector_metadata =
+            payment.and_then(|payment| payment.get_connector_metadata().ok().flatten());
+        assert_eq!(
+            connector_metadata
+                .as_ref()
+                .and_then(|metadata| metadata.get("created_date")),
+            Some(&serde_json::json!("2024-05-21T13:30:00.000Z"))
+        );
+
+        // A date in an unexpected format doesn't fail the payment
+        let response = r#"{"id":"D-4-1","status":"PAID","created_date":"21/05/2024"}"#;
+        let payment = serde_json::from_str::<DlocalPaymentsResponse>(response).ok();
+        assert!(payment.is_some_and(|payment| payment.created_date.is_none()));
+    }
+
+    #[test]
+    fn test_card_holder_name() {
+        let holder_name = |card_holder_name: Option<&str>, payer_name: Option<&str>| {
+            get_card_holder_name(
+                card_holder_name.map(|name| Secret::new(name.to_string())),
+                payer_name
+                    .map(|name| Secret::new(name.to_string()))
+                    .as_ref(),
+            )
+            .ok()
+            .map(Secret::expose)
+        };
+        let name = |name: &str| Some(name.to_string());
+        assert_eq!(
+            holder_name(Some("Jane Doe"), Some("John Doe")),
+            name("Jane Doe")
+        );
+        // The payer's name stands in for a missing or blank card holder name
+        assert_eq!(holder_name(None, Some("John Doe")), name("John Doe"));
+        assert_eq!(holder_name(Some("  "), Some("John Doe")), name("John Doe"));
+        let missing_holder_name = get_card_holder_name(Some(Secret::new("".to_string())), None);
+        assert!(missing_holder_name.is_err_and(|error| matches!(
+            error.current_context(),
+            ConnectorError::MissingRequiredField {
+                field_name: "card_holder_name"
+            }
+        )));
+
+        // Hosted page payments leave the holder name to the shopper rather than sending it blank
+        let card = serde_json::to_value(Card {
+            capture: true,
+            ..Default::default()
+        })
+        .ok();
+        assert!(card.is_some_and(|card| card.get("holder_name").is_none()));
+    }
+
+    #[test]
+    fn test_card_holder_name_whitespace() {
+        let holder_name = get_card_holder_name(Some(Secret::new(" Jane   Doe ".to_string())), None);
+        assert_eq!(
+            holder_name.ok().map(Secret::expose),
+            Some("Jane Doe".to_string())
+        );
+        let address = AddressDetails {
+            first_name: Some(Secret::new(" John  Paul".to_string())),
+            last_name: Some(Secret::new("Doe ".to_string())),
+            ..Default::default()
+        };
+        assert_eq!(
+            get_payer_name(&address).map(Secret::expose),
+            Some("John Paul Doe".to_string())
+        );
+        // Billing names without a last name are sent as is
+        let address = AddressDetails {
+            first_name: Some(Secret::new("John".to_string())),
+            ..Default::default()
+        };
+        assert_eq!(
+            get_payer_name(&address).map(Secret::expose),
+            Some("John".to_string())
+        );
+    }
+
+    #[test]
+    fn test_card_bin_and_issuer_country_metadata() {
+        let response = r#"{
+            "id": "D-4-2",
+            "status": "PAID",
+            "card": {
+                "holder_name": "Thomas",
+                "brand": "MC",
+                "last4": "4444",
+                "bin": "555555",
+                "issuer_country": "BR"
+            }
+        }"#;
+        let connector_metadata = serde_json::from_str::<DlocalPaymentsResponse>(response)
+            .ok()
+            .and_then(|response| response.get_connector_metadata().ok().flatten());
+        assert_eq!(
+            connector_metadata,
+            Some(serde_json::json!({
+                "card": {
+                    "brand": "MC",
+                    "last4": "4444",
+                    "first6": "555555",
+                    "issuer_country": "BR"
+                }
+            }))
+        );
+
+        // Payments made with other payment methods have no card block
+        let response = r#"{"id":"D-4-3","status":"PENDING","ticket":{"number":"123"}}"#;
+        let payment = serde_json::from_str::<DlocalPaymentsResponse>(response).ok();
+        assert!(payment.is_some_and(|payment| payment.card.is_none()));
+    }
+
+    #[test]
+    fn test_connector_response_reference_id() {
+        assert_eq!(
+            get_connector_response_reference_id(Some("pay_1_1".to_string()), "D-4-1"),
+            Some("pay_1_1".to_string())
+        );
+        // Redirect flows may come back without the order id
+        assert_eq!(
+            get_connector_response_reference_id(None, "D-4-1"),
+            Some("D-4-1".to_string())
+        );
+    }
+
+    #[test]
+    fn test_response_reference_id_fixtures() {
+        let with_order_id = r#"{"id":"D-4-1","status":"PAID","order_id":"pay_1_1"}"#;
+        let without_order_id = r#"{"id":"D-4-1","status":"PAID"}"#;
+        let sync_reference_id = |response| {
+            serde_json::from_str::<DlocalPaymentsResponse>(response)
+                .ok()
+                .and_then(|sync| get_connector_response_reference_id(sync.order_id, &sync.id))
+        };
+        assert_eq!(
+            sync_reference_id(with_order_id),
+            Some("pay_1_1".to_string())
+        );
+        assert_eq!(
+            sync_reference_id(without_order_id),
+            Some("D-4-1".to_string())
+        );
+        let capture_reference_id = |response| {
+            serde_json::from_str::<DlocalPaymentsCaptureResponse>(response)
+                .ok()
+                .and_then(|capture| {
+                    get_connector_response_reference_id(capture.order_id, &capture.id)
+                })
+        };
+        assert_eq!(
+            capture_reference_id(with_order_id),
+            Some("pay_1_1".to_string())
+        );
+        assert_eq!(
+            capture_reference_id(without_order_id),
+            Some("D-4-1".to_string())
+        );
+        let cancel_reference_id = |response| {
+            serde_json::from_str::<DlocalPaymentsCancelResponse>(response)
+                .ok()
+                .and_then(|cancel| get_connector_response_reference_id(cancel.order_id, &cancel.id))
+        };
+        let cancelled = r#"{"id":"D-4-1","status":"CANCELLED"}"#;
+        assert_eq!(cancel_reference_id(cancelled), Some("D-4-1".to_string()));
+    }
+
+    #[test]
+    fn test_smart_fields_token_request() {
+        let browser_info = BrowserInformation {
+            color_depth: Some(24),
+            language: Some("pt-BR".to_string()),
+            screen_height: Some(1080),
+            screen_width: Some(1920),
+            accept_header: Some("text/html".to_string()),
+            user_agent: Some("Mozilla/5.0".to_string()),
+            ..Default::default()
+        };
+        let request = get_three_dsecure(AuthenticationType::ThreeDs, Some(&browser_info))
+            .ok()
+            .map(|three_dsecure| DlocalPaymentsRequest {
+                payment_method_id: PaymentMethodId::Card,
+                payment_method_flow: get_payment_method_flow(
+                    PaymentMethodId::Card,
+                    AuthenticationType::ThreeDs,
+                ),
+                card: Some(get_tokenized_card(
+                    Secret::new("John Doe".to_string()),
+                    Secret::new("CV-ee2a6ba5-4a3e-4b29-9d18-1b3c2f1e6a7d".to_string()),
+                    true,
+                )),
+                three_dsecure,
+                ..Default::default()
+            })
+            .and_then(|request| serde_json::to_value(request).ok());
+        assert_eq!(
+            request.as_ref().and_then(|request| request.get("card")),
+            Some(&serde_json::json!({
+                "holder_name": "John Doe",
+                "token": "CV-ee2a6ba5-4a3e-4b29-9d18-1b3c2f1e6a7d",
+                "capture": true
+            }))
+        );
+        assert_eq!(
+            request
+                .as_ref()
+                .and_then(|request| request.pointer("/three_dsecure/force")),
+            Some(&serde_json::json!(true))
+        );
+        assert_eq!(
+            request
+                .as_ref()
+                .and_then(|request| request.get("payment_method_flow")),
+            Some(&serde_json::json!("REDIRECT"))
+        );
+    }
+
+    #[test]
+    fn test_refund_retry_classification() {
+        let retry = |response: &str| {
+            serde_json::from_str::<RefundResponse>(response)
+                .ok()
+                .map(|refund| refund.get_retry_classification())
+        };
+        let code = |code: &str| Some(code.to_string());
+        assert_eq!(
+            retry(r#"{"id":"REF-1","status":"PENDING","status_code":"100"}"#),
+            Some(DlocalRefundRetry::Retryable { code: code("100") })
+        );
+        assert_eq!(
+            retry(r#"{"id":"REF-1","status":"REJECTED","status_code":"306"}"#),
+            Some(DlocalRefundRetry::Retryable { code: code("306") })
+        );
+        assert_eq!(
+            retry(r#"{"id":"REF-1","status":"REJECTED","status_code":"701"}"#),
+            Some(DlocalRefundRetry::Terminal { code: code("701") })
+        );
+        assert_eq!(
+            retry(r#"{"id":"REF-1","status":"SUCCESS"}"#),
+            Some(DlocalRefundRetry::Terminal { code: None })
+        );
+    }
+
+    #[test]
+    fn test_error_response_attempt_status() {
+        let error_response = |body: &'static str| {
+            Dlocal::new()
+                .build_error_response(
+                    Response {
+                        headers: None,
+                        response: body.into(),
+                        status_code: 400,
+                    },
+                    None,
+                )
+                .ok()
+        };
+        // Card declines fail the attempt, retrying them will not change the outcome
+        let decline = error_response(r#"{"code":300,"message":"Payment rejected"}"#);
+        assert!(decline.is_some_and(|error_response| {
+            error_response.code == "300"
+                && error_response.attempt_status == Some(AttemptStatus::Failure)
+        }));
+        // Processing errors keep dLocal's code and leave the attempt status to the flow
+        let processing_error =
+            error_response(r#"{"code":5008,"message":"Token not found or inactive"}"#);
+        assert!(processing_error.is_some_and(|error_response| {
+            error_response.code == "5008"
+                && error_response.message == "Token not found or inactive"
+                && error_response.attempt_status.is_none()
+        }));
+    }
 }
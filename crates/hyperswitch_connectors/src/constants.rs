@@ -11,6 +11,7 @@ pub(crate) mod headers {
     pub(crate) const MESSAGE_SIGNATURE: &str = "Message-Signature";
     pub(crate) const MERCHANT_ID: &str = "Merchant-ID";
     pub(crate) const REQUEST_ID: &str = "request-id";
+    pub(crate) const RETRY_AFTER: &str = "Retry-After";
     pub(crate) const NONCE: &str = "nonce";
     pub(crate) const TIMESTAMP: &str = "Timestamp";
     pub(crate) const TOKEN: &str = "token";
@@ -18,6 +19,7 @@ pub(crate) mod headers {
     pub(crate) const X_CC_API_KEY: &str = "X-CC-Api-Key";
     pub(crate) const X_CC_VERSION: &str = "X-CC-Version";
     pub(crate) const X_DATE: &str = "X-Date";
+    pub(crate) const X_IDEMPOTENCY_KEY: &str = "X-Idempotency-Key";
     pub(crate) const X_LOGIN: &str = "X-Login";
     pub(crate) const X_NN_ACCESS_KEY: &str = "X-NN-Access-Key";
     pub(crate) const X_TRANS_KEY: &str = "X-Trans-Key";
@@ -451,7 +451,6 @@ default_imp_for_complete_authorize!(
     connectors::Coingate,
     connectors::Cryptopay,
     connectors::Datatrans,
-    connectors::Dlocal,
     connectors::Elavon,
     connectors::Fiserv,
     connectors::Fiservemea,
@@ -1205,7 +1204,6 @@ default_imp_for_accept_dispute!(
     connectors::Datatrans,
     connectors::Deutschebank,
     connectors::Digitalvirgo,
-    connectors::Dlocal,
     connectors::Elavon,
     connectors::Fiserv,
     connectors::Fiservemea,
@@ -1292,7 +1290,6 @@ default_imp_for_submit_evidence!(
     connectors::Datatrans,
     connectors::Deutschebank,
     connectors::Digitalvirgo,
-    connectors::Dlocal,
     connectors::Elavon,
     connectors::Fiserv,
     connectors::Fiservemea,
@@ -1554,7 +1551,6 @@ default_imp_for_payouts!(
     connectors::Coingate,
     connectors::Deutschebank,
     connectors::Digitalvirgo,
-    connectors::Dlocal,
     connectors::Elavon,
     connectors::Fiserv,
     connectors::Fiservemea,
@@ -1642,7 +1638,6 @@ default_imp_for_payouts_create!(
     connectors::Datatrans,
     connectors::Deutschebank,
     connectors::Digitalvirgo,
-    connectors::Dlocal,
     connectors::Elavon,
     connectors::Fiserv,
     connectors::Fiservemea,
@@ -1731,7 +1726,6 @@ default_imp_for_payouts_retrieve!(
     connectors::Datatrans,
     connectors::Deutschebank,
     connectors::Digitalvirgo,
-    connectors::Dlocal,
     connectors::Elavon,
     connectors::Fiserv,
     connectors::Fiservemea,
@@ -1908,7 +1902,6 @@ default_imp_for_payouts_fulfill!(
     connectors::Datatrans,
     connectors::Deutschebank,
     connectors::Digitalvirgo,
-    connectors::Dlocal,
     connectors::Elavon,
     connectors::Fiserv,
     connectors::Fiservemea,
@@ -1997,7 +1990,6 @@ default_imp_for_payouts_cancel!(
     connectors::Datatrans,
     connectors::Deutschebank,
     connectors::Digitalvirgo,
-    connectors::Dlocal,
     connectors::Elavon,
     connectors::Fiserv,
     connectors::Fiservemea,
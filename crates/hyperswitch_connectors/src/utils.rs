@@ -1006,6 +1006,35 @@ pub enum CardIssuer {
     CarteBlanche,
 }
 
+/// Pads a card expiry month to two digits, e.g. "7" to "07", rejecting anything outside 01-12
+fn get_card_expiry_month_2_digit(month: &Secret<String>) -> Result<Secret<String>, Error> {
+    let month = month.peek().trim();
+    let is_numeric = month.chars().all(|character| character.is_ascii_digit());
+    match month.parse::<u8>() {
+        Ok(month_number @ 1..=12) if is_numeric && month.len() <= 2 => {
+            Ok(Secret::new(format!("{month_number:02}")))
+        }
+        _ => Err(errors::ConnectorError::InvalidDataFormat {
+            field_name: "card_exp_month",
+        }
+        .into()),
+    }
+}
+
+/// Expands a two digit card expiry year to four digits, assuming the card expires this century
+fn get_card_expiry_year_4_digit(year: &Secret<String>) -> Result<Secret<String>, Error> {
+    let year = year.peek().trim();
+    let is_numeric = year.chars().all(|character| character.is_ascii_digit());
+    match year.len() {
+        2 if is_numeric => Ok(Secret::new(format!("20{year}"))),
+        4 if is_numeric => Ok(Secret::new(year.to_string())),
+        _ => Err(errors::ConnectorError::InvalidDataFormat {
+            field_name: "card_exp_year",
+        }
+        .into()),
+    }
+}
+
 pub trait CardData {
     fn get_card_expiry_year_2_digit(&self) -> Result<Secret<String>, errors::ConnectorError>;
     fn get_card_issuer(&self) -> Result<CardIssuer, Error>;
@@ -1022,6 +1051,8 @@ pub trait CardData {
     fn get_expiry_year_as_i32(&self) -> Result<Secret<i32>, Error>;
     fn get_expiry_year_as_4_digit_i32(&self) -> Result<Secret<i32>, Error>;
     fn get_cardholder_name(&self) -> Result<Secret<String>, Error>;
+    fn get_validated_expiry_month(&self) -> Result<Secret<String>, Error>;
+    fn get_validated_expiry_year_4_digit(&self) -> Result<Secret<String>, Error>;
 }
 
 impl CardData for Card {
@@ -1113,6 +1144,12 @@ impl CardData for Card {
             .clone()
             .ok_or_else(missing_field_err("card.card_holder_name"))
     }
+    fn get_validated_expiry_month(&self) -> Result<Secret<String>, Error> {
+        get_card_expiry_month_2_digit(&self.card_exp_month)
+    }
+    fn get_validated_expiry_year_4_digit(&self) -> Result<Secret<String>, Error> {
+        get_card_expiry_year_4_digit(&self.card_exp_year)
+    }
 }
 
 impl CardData for CardDetailsForNetworkTransactionId {
@@ -1204,6 +1241,12 @@ impl CardData for CardDetailsForNetworkTransactionId {
             .clone()
             .ok_or_else(missing_field_err("card.card_holder_name"))
     }
+    fn get_validated_expiry_month(&self) -> Result<Secret<String>, Error> {
+        get_card_expiry_month_2_digit(&self.card_exp_month)
+    }
+    fn get_validated_expiry_year_4_digit(&self) -> Result<Secret<String>, Error> {
+        get_card_expiry_year_4_digit(&self.card_exp_year)
+    }
 }
 
 #[track_caller]
@@ -1838,6 +1881,7 @@ pub trait PaymentsSetupMandateRequestData {
     fn get_return_url(&self) -> Result<String, Error>;
     fn get_webhook_url(&self) -> Result<String, Error>;
     fn get_optional_language_from_browser_info(&self) -> Option<String>;
+    fn get_ip_address_as_optional(&self) -> Option<Secret<String, IpAddress>>;
 }
 
 impl PaymentsSetupMandateRequestData for SetupMandateRequestData {
@@ -1872,6 +1916,13 @@ impl PaymentsSetupMandateRequestData for SetupMandateRequestData {
             .clone()
             .and_then(|browser_info| browser_info.language)
     }
+    fn get_ip_address_as_optional(&self) -> Option<Secret<String, IpAddress>> {
+        self.browser_info.clone().and_then(|browser_info| {
+            browser_info
+                .ip_address
+                .map(|ip| Secret::new(ip.to_string()))
+        })
+    }
 }
 
 pub trait PaymentMethodTokenizationRequestData {
@@ -2382,12 +2433,45 @@ impl QrImage {
 
 #[cfg(test)]
 mod tests {
+    use masking::Secret;
+
     use crate::utils;
     #[test]
     fn test_image_data_source_url() {
         let qr_image_data_source_url = utils::QrImage::new_from_data("Hyperswitch".to_string());
         assert!(qr_image_data_source_url.is_ok());
     }
+
+    #[test]
+    fn test_card_expiry_month_2_digit() {
+        let month = |month: &str| {
+            utils::get_card_expiry_month_2_digit(&Secret::new(month.to_string()))
+                .ok()
+                .map(masking::ExposeInterface::expose)
+        };
+        assert_eq!(month("7"), Some("07".to_string()));
+        assert_eq!(month("12"), Some("12".to_string()));
+        assert_eq!(month("0"), None);
+        assert_eq!(month("13"), None);
+        assert_eq!(month("007"), None);
+        assert_eq!(month("+7"), None);
+        assert_eq!(month("ab"), None);
+    }
+
+    #[test]
+    fn test_card_expiry_year_4_digit() {
+        let year = |year: &str| {
+            utils::get_card_expiry_year_4_digit(&Secret::new(year.to_string()))
+                .ok()
+                .map(masking::ExposeInterface::expose)
+        };
+        assert_eq!(year("26"), Some("2026".to_string()));
+        assert_eq!(year("99"), Some("2099".to_string()));
+        assert_eq!(year("00"), Some("2000".to_string()));
+        assert_eq!(year("2026"), Some("2026".to_string()));
+        assert_eq!(year("202"), None);
+        assert_eq!(year("2x"), None);
+    }
 }
 
 pub fn is_mandate_supported(
@@ -2953,6 +3037,12 @@ impl CardData for api_models::payouts::CardPayout {
             .clone()
             .ok_or_else(missing_field_err("card.card_holder_name"))
     }
+    fn get_validated_expiry_month(&self) -> Result<Secret<String>, Error> {
+        get_card_expiry_month_2_digit(&self.expiry_month)
+    }
+    fn get_validated_expiry_year_4_digit(&self) -> Result<Secret<String>, Error> {
+        get_card_expiry_year_4_digit(&self.expiry_year)
+    }
 }
 
 pub trait NetworkTokenData {
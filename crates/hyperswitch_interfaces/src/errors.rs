@@ -110,6 +110,13 @@ pub enum ConnectorError {
     InSufficientBalanceInPaymentMethod,
     #[error("Server responded with Request Timeout")]
     RequestTimeoutReceived,
+    #[error("{field_name} is longer than the {max_length} characters allowed by {connector}")]
+    MaxFieldLengthViolated {
+        connector: &'static str,
+        field_name: &'static str,
+        max_length: usize,
+        received_length: usize,
+    },
     #[error("The given currency method is not configured with the given connector")]
     CurrencyNotSupported {
         message: String,
@@ -2,8 +2,59 @@
 //!
 //! There is not alias type by design.
 
+use std::fmt;
+
+use super::{ExposeInterface, PeekInterface, ZeroizableSecret};
 #[cfg(feature = "serde")]
 use super::{SerializableSecret, Serialize};
 
 #[cfg(feature = "serde")]
 impl<S: Serialize> SerializableSecret for Box<S> {}
+
+/// Secret allocated on the heap, the value is zeroized when the box is dropped.
+///
+/// Useful for large secrets like PEM encoded keys or long tokens.
+pub struct BoxedSecret<S: ZeroizableSecret>(Box<S>);
+
+impl<S: ZeroizableSecret> BoxedSecret<S> {
+    /// Move the secret to the heap
+    pub fn new(secret: S) -> Self {
+        Self(Box::new(secret))
+    }
+}
+
+impl<S: ZeroizableSecret> PeekInterface<S> for BoxedSecret<S> {
+    fn peek(&self) -> &S {
+        &self.0
+    }
+}
+
+impl<S: ZeroizableSecret + Default> ExposeInterface<S> for BoxedSecret<S> {
+    fn expose(mut self) -> S {
+        std::mem::take(self.0.as_mut())
+    }
+}
+
+impl<S: ZeroizableSecret> From<Box<S>> for BoxedSecret<S> {
+    fn from(secret: Box<S>) -> Self {
+        Self(secret)
+    }
+}
+
+impl<S: ZeroizableSecret + Clone> Clone for BoxedSecret<S> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<S: ZeroizableSecret> fmt::Debug for BoxedSecret<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "BoxedSecret([REDACTED])")
+    }
+}
+
+impl<S: ZeroizableSecret> Drop for BoxedSecret<S> {
+    fn drop(&mut self) {
+        self.0.as_mut().zeroize();
+    }
+}
@@ -0,0 +1,49 @@
+//! Secret collection types
+//!
+//! Collections wrapped in a [`Secret`](crate::Secret) with the [`RedactElements`] strategy
+//! show how many elements they hold, but never the elements themselves.
+
+use std::{collections::HashMap, fmt};
+
+use super::Strategy;
+#[cfg(feature = "serde")]
+use super::{SerializableSecret, Serialize};
+
+#[cfg(feature = "serde")]
+impl<K: Serialize, V: Serialize> SerializableSecret for HashMap<K, V> {}
+
+/// Debug every element of a collection as `*** REDACTED ***`, map keys are kept.
+///
+/// ```
+/// use masking::{RedactElements, Secret};
+///
+/// let cards: Secret<Vec<String>, RedactElements> =
+///     Secret::new(vec!["4111111111111111".to_string(), "5555555555554444".to_string()]);
+///
+/// assert_eq!(format!("{cards:?}"), "[*** REDACTED ***, *** REDACTED ***]");
+/// ```
+pub enum RedactElements {}
+
+struct Redacted;
+
+impl fmt::Debug for Redacted {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("*** REDACTED ***")
+    }
+}
+
+impl<T> Strategy<Vec<T>> for RedactElements {
+    fn fmt(value: &Vec<T>, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_list()
+            .entries(value.iter().map(|_| Redacted))
+            .finish()
+    }
+}
+
+impl<K: fmt::Debug, V, S> Strategy<HashMap<K, V, S>> for RedactElements {
+    fn fmt(value: &HashMap<K, V, S>, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_map()
+            .entries(value.keys().map(|key| (key, Redacted)))
+            .finish()
+    }
+}
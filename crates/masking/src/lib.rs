@@ -11,7 +11,7 @@ pub use zeroize::{self, DefaultIsZeroes, Zeroize as ZeroizableSecret};
 
 mod strategy;
 
-pub use strategy::{Strategy, WithType, WithoutType};
+pub use strategy::{Strategy, WithLastFour, WithType, WithoutType};
 mod abs;
 pub use abs::{ExposeInterface, ExposeOptionInterface, PeekInterface, SwitchStrategy};
 
@@ -25,6 +25,8 @@ extern crate alloc;
 
 #[cfg(feature = "alloc")]
 mod boxed;
+#[cfg(feature = "alloc")]
+pub use boxed::BoxedSecret;
 
 #[cfg(feature = "bytes")]
 mod bytes;
@@ -37,11 +39,17 @@ mod string;
 #[cfg(feature = "alloc")]
 mod vec;
 
+#[cfg(feature = "alloc")]
+mod collections;
+#[cfg(feature = "alloc")]
+pub use collections::RedactElements;
+
 #[cfg(feature = "serde")]
 mod serde;
 #[cfg(feature = "serde")]
 pub use crate::serde::{
-    masked_serialize, Deserialize, ErasedMaskSerialize, SerializableSecret, Serialize,
+    masked_serialize, Deserialize, ErasedMaskSerialize, SecretSerdeValue, SerializableSecret,
+    Serialize,
 };
 
 /// This module should be included with asterisk.
@@ -2,6 +2,8 @@
 
 use std::{fmt, marker::PhantomData};
 
+use subtle::ConstantTimeEq;
+
 use crate::{strategy::Strategy, PeekInterface, StrongSecret};
 
 /// Secret thing.
@@ -36,6 +38,22 @@ use crate::{strategy::Strategy, PeekInterface, StrongSecret};
 ///
 /// assert_eq!("hello", &format!("{:?}", my_secret));
 /// ```
+///
+/// ## Comparison
+///
+/// The [`PartialEq`] implementation of [`Secret`] compares the inner values with their own
+/// [`PartialEq`], which returns as soon as a difference is found. Secrets which could be
+/// guessed through timing (CVV, tokens, keys) should be compared with [`Secret::ct_eq`], which
+/// takes the same time wherever the values differ. [`StrongSecret<String>`] and
+/// [`StrongSecret<Vec<u8>>`] are always compared in constant time.
+///
+/// ```
+/// use masking::Secret;
+///
+/// let cvv: Secret<String> = Secret::new("123".to_string());
+///
+/// assert!(cvv.ct_eq(&Secret::new("123".to_string())));
+/// ```
 pub struct Secret<Secret, MaskingStrategy = crate::WithType>
 where
     MaskingStrategy: Strategy<Secret>,
@@ -87,6 +105,23 @@ where
     }
 }
 
+impl<SecretValue, MaskingStrategy> Secret<SecretValue, MaskingStrategy>
+where
+    SecretValue: AsRef<[u8]>,
+    MaskingStrategy: Strategy<SecretValue>,
+{
+    /// Compare two secrets in constant time
+    ///
+    /// Only the length of the secrets is leaked, secrets of different lengths are never equal.
+    pub fn ct_eq(&self, other: &Self) -> bool {
+        bool::from(
+            self.inner_secret
+                .as_ref()
+                .ct_eq(other.inner_secret.as_ref()),
+        )
+    }
+}
+
 impl<SecretValue, MaskingStrategy> PeekInterface<SecretValue>
     for Secret<SecretValue, MaskingStrategy>
 where
@@ -32,6 +32,12 @@ impl SerializableSecret for url::Url {}
 #[cfg(feature = "time")]
 impl SerializableSecret for time::Date {}
 
+/// Arbitrary JSON holding secret information, such as connector metadata where PII is mixed with
+/// other fields.
+///
+/// The value is redacted as a whole in [`Debug`] while serialization emits the JSON as is.
+pub type SecretSerdeValue = Secret<Value>;
+
 impl<'de, T, I> Deserialize<'de> for Secret<T, I>
 where
     T: Clone + de::DeserializeOwned + Sized,
@@ -27,3 +27,21 @@ impl<T> Strategy<T> for WithoutType {
         fmt.write_str("*** ***")
     }
 }
+
+/// Debug only the last four characters, e.g. `************1111` for a card number
+///
+/// Values of four characters or less are masked entirely.
+pub enum WithLastFour {}
+
+impl<T> Strategy<T> for WithLastFour
+where
+    T: AsRef<str>,
+{
+    fn fmt(value: &T, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let value = value.as_ref();
+        let length = value.chars().count();
+        let masked_length = if length > 4 { length - 4 } else { length };
+        let last_four = value.chars().skip(masked_length).collect::<String>();
+        write!(fmt, "{}{}", "*".repeat(masked_length), last_four)
+    }
+}
@@ -387,7 +387,9 @@ impl ConnectorData {
                 enums::Connector::Digitalvirgo => {
                     Ok(ConnectorEnum::Old(Box::new(connector::Digitalvirgo::new())))
                 }
-                enums::Connector::Dlocal => Ok(ConnectorEnum::Old(Box::new(&connector::Dlocal))),
+                enums::Connector::Dlocal => {
+                    Ok(ConnectorEnum::Old(Box::new(connector::Dlocal::new())))
+                }
                 #[cfg(feature = "dummy_connector")]
                 enums::Connector::DummyConnector1 => Ok(ConnectorEnum::Old(Box::new(
                     &connector::DummyConnector::<1>,
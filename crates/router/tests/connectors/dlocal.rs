@@ -18,7 +18,7 @@ impl utils::Connector for DlocalTest {
     fn get_data(&self) -> api::ConnectorData {
         use router::connector::Dlocal;
         utils::construct_connector_data_old(
-            Box::new(&Dlocal),
+            Box::new(Dlocal::new()),
             types::Connector::Dlocal,
             api::GetToken::Connector,
             None,
@@ -444,10 +444,39 @@ pub fn get_payment_info() -> PaymentInfo {
         )),
         auth_type: None,
         access_token: None,
-        connector_meta_data: None,
+        // The sandbox payments are made without a payer document
+        connector_meta_data: Some(serde_json::json!({ "document_mode": "SANDBOX" })),
         ..Default::default()
     }
 }
 // Connector dependent test cases goes here
 
 // [#478]: add unit tests for non 3DS, wallets & webhooks in connector tests
+
+// Retries of the same attempt have to reuse the idempotency key.
+#[test]
+fn should_send_the_same_idempotency_key_for_the_same_authorize_request() {
+    use router::{
+        configs::settings::Connectors, connector::Dlocal, services::ConnectorIntegration,
+    };
+
+    let request: types::PaymentsAuthorizeRouterData = CONNECTOR.generate_data(
+        utils::PaymentAuthorizeType::default().0,
+        Some(get_payment_info()),
+    );
+    let connectors = Connectors::default();
+    let idempotency_key = || {
+        ConnectorIntegration::<
+            api::Authorize,
+            types::PaymentsAuthorizeData,
+            types::PaymentsResponseData,
+        >::get_headers(Dlocal::new(), &request, &connectors)
+        .expect("Authorize request headers")
+        .into_iter()
+        .find(|(name, _)| name == "X-Idempotency-Key")
+        .map(|(_, value)| value.into_inner())
+    };
+    let first_attempt = idempotency_key();
+    assert!(first_attempt.is_some());
+    assert_eq!(first_attempt, idempotency_key());
+}